@@ -0,0 +1,64 @@
+// Comparing walking objects with running them as bytecode, on the same programs
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use lithia::lisp::LispBuilder;
+
+const PROGRAMS: &[(&str, &str)] = &[
+    (
+        "fib",
+        "(defunc fib (n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))
+         (fib 15)",
+    ),
+    (
+        "loop",
+        "(= i 0)
+         (= total 0)
+         (while (< i 1000)
+           (= total (+ total (* i 2)))
+           (= i (+ i 1)))",
+    ),
+    (
+        "lcm",
+        "(defunc lcm (a b)
+           (defunc get-lcm (a b i)
+             (if (!= (% (/ (* a i) b) 1) 0)
+               (get-lcm a b (+ i 1))
+               (* a i)))
+           (get-lcm a b 1))
+         (lcm 40 33)",
+    ),
+    (
+        "lists",
+        "(reduce + (map (func (x) (* x x)) (filter even? (range 0 200))))",
+    ),
+];
+
+fn backends(c: &mut Criterion) {
+    for (name, program) in PROGRAMS {
+        let mut group = c.benchmark_group(*name);
+
+        for bytecode in [false, true] {
+            let backend = if bytecode { "bytecode" } else { "tree" };
+
+            group.bench_with_input(BenchmarkId::from_parameter(backend), program, |b, p| {
+                // Programs define globals, so each run needs a new interpreter
+                b.iter_batched(
+                    || {
+                        LispBuilder::new()
+                            .add_default_envs()
+                            .unwrap()
+                            .use_bytecode(bytecode)
+                            .build()
+                    },
+                    |mut lisp| lisp.eval(p).unwrap(),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, backends);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+// The cost of calling lisp functions, with recursive functions defined once and then called
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lithia::{lisp::LispBuilder, object::Object, rc::Rc};
+
+const FUNCTIONS: &[(&str, &str, &str)] = &[
+    (
+        "get-lcm",
+        "(defunc get-lcm (a b i)
+           (if (!= (% (/ (* a i) b) 1) 0)
+             (get-lcm a b (+ i 1))
+             (* a i)))",
+        "(get-lcm 97 89 1)",
+    ),
+    (
+        "fib",
+        "(defunc fib (n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))",
+        "(fib 15)",
+    ),
+    (
+        "count",
+        "(defunc count (n &key step)
+           (if (<= n 0) n (count (- n step) :step step)))",
+        "(count 100 :step 1)",
+    ),
+];
+
+fn calls(c: &mut Criterion) {
+    for (name, definition, call) in FUNCTIONS {
+        let mut group = c.benchmark_group(format!("call {}", name));
+
+        for bytecode in [false, true] {
+            let backend = if bytecode { "bytecode" } else { "tree" };
+
+            let mut lisp = LispBuilder::new()
+                .add_default_envs()
+                .unwrap()
+                .use_bytecode(bytecode)
+                .build();
+
+            lisp.eval(definition).unwrap();
+
+            // Read the call once, so only running it is measured
+            let call: Vec<Rc<Object>> = lisp.read(call).unwrap();
+
+            group.bench_function(BenchmarkId::from_parameter(backend), |b| {
+                b.iter(|| lisp.eval_objects(call.clone()).unwrap())
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, calls);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+// The cost of finding variables by name, in loops like powersoftwo.lisp with plenty of other
+// globals around
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use lithia::lisp::LispBuilder;
+
+const PROGRAMS: &[(&str, &str)] = &[
+    (
+        "globals",
+        "(= x 2)
+         (= n 0)
+         (while (< n 100)
+           (= x (* x 2))
+           (= n (+ n 1)))",
+    ),
+    (
+        "locals",
+        "(defunc powers (x n)
+           (while (< n 100)
+             (= x (* x 2))
+             (= n (+ n 1)))
+           x)
+         (powers 2 0)",
+    ),
+];
+
+fn lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookups");
+
+    for (name, program) in PROGRAMS {
+        group.bench_with_input(BenchmarkId::from_parameter(name), program, |b, p| {
+            b.iter_batched(
+                || {
+                    let mut lisp = LispBuilder::new().add_default_envs().unwrap().build();
+
+                    // Enough other variables that finding one isn't trivially cheap
+                    for i in 0..200 {
+                        lisp.eval(&format!("(def variable-{} {})", i, i)).unwrap();
+                    }
+
+                    lisp
+                },
+                |mut lisp| lisp.eval(p).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, lookups);
+criterion_main!(benches);
@@ -0,0 +1,318 @@
+/*!
+Macros for exposing rust functions and structs to [lithia](https://crates.io/crates/lithia)
+
+These are used through lithia's `macros` feature, as `#[lithia::function]` and
+`#[derive(lithia::LispType)]`, rather than by depending on this crate directly. Either way what is
+generated is added to an interpreter with `LispBuilder::register`
+
+# Example: function
+
+Parameters and return values are converted as with `LispBuilder::add_func`. The lisp name is the
+rust one with dashes for underscores, unless given as `#[lithia::function(name = "...")]`
+
+```rust
+use lithia::{lisp::LispBuilder, object::Object};
+
+#[lithia::function]
+fn square_sum(a: f32, b: f32) -> f32 {
+    a * a + b * b
+}
+
+let mut lisp = LispBuilder::new().register::<square_sum>().unwrap().build();
+
+assert_eq!(*lisp.eval("(square-sum 3 4)").unwrap(), Object::Number(25.0));
+```
+
+# Example: struct
+
+Registering a struct named `Point` adds `make-point` taking each field in order, `point?`, and
+`point-x` for each field giving a copy of its value. Names can be changed with
+`#[lisp(name = "...")]`, and fields hidden with `#[lisp(skip)]`, being made with their default
+value instead
+
+```rust
+use lithia::{lisp::LispBuilder, object::Object};
+
+#[derive(lithia::LispType)]
+struct Point {
+    x: f32,
+    y: f32,
+    #[lisp(skip)]
+    cache: Option<f32>,
+}
+
+#[lithia::function]
+fn origin() -> Point {
+    Point {
+        x: 0.0,
+        y: 0.0,
+        cache: None,
+    }
+}
+
+let mut lisp = LispBuilder::new()
+    .add_default_envs()
+    .unwrap()
+    .register::<Point>()
+    .unwrap()
+    .register::<origin>()
+    .unwrap()
+    .build();
+
+lisp.eval("(def p (make-point 1 2))").unwrap();
+
+assert_eq!(*lisp.eval("(point-y p)").unwrap(), Object::Number(2.0));
+assert_eq!(*lisp.eval("(point? (origin))").unwrap(), Object::True);
+assert!(lisp.eval("(point-x 1)").is_err());
+```
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, FnArg, ItemFn,
+    LitStr,
+};
+
+// Give a rust function to lisp, registered through a struct of the same name
+#[proc_macro_attribute]
+pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+
+    let mut name = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else {
+            Err(meta.error("expected `name = \"...\"`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
+    expand_function(name, func)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_function(name: Option<String>, func: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &func.sig;
+
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "functions given to lisp can't be generic",
+        ));
+    }
+
+    if let Some(FnArg::Receiver(r)) = sig.inputs.first() {
+        return Err(Error::new_spanned(
+            r,
+            "only free functions can be given to lisp, not methods",
+        ));
+    }
+
+    if let Some(a) = &sig.asyncness {
+        return Err(Error::new_spanned(
+            a,
+            "async functions can't be given to lisp",
+        ));
+    }
+
+    let ident = &sig.ident;
+    let vis = &func.vis;
+    let name = name.unwrap_or_else(|| ident.unraw().to_string().replace('_', "-"));
+
+    // A struct with braces only takes the name as a type, so it doesn't clash with the function
+    Ok(quote! {
+        #func
+
+        #[allow(non_camel_case_types, dead_code)]
+        #vis struct #ident {}
+
+        impl ::lithia::lisp::Register for #ident {
+            fn register(
+                builder: ::lithia::lisp::LispBuilder,
+            ) -> ::core::result::Result<::lithia::lisp::LispBuilder, ::lithia::errors::LispError> {
+                builder.add_func(#name, #ident)
+            }
+        }
+    })
+}
+
+// Give a struct to lisp, stored in objects as a rust type, with a constructor, a predicate and
+// a function getting each field
+#[proc_macro_derive(LispType, attributes(lisp))]
+pub fn derive_lisp_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_lisp_type(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_lisp_type(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "types given to lisp can't be generic",
+        ));
+    }
+
+    let fields: Vec<_> = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => f.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(f) => {
+                return Err(Error::new_spanned(
+                    f,
+                    "only structs with named fields can be given to lisp",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "only structs can be given to lisp",
+            ))
+        }
+    };
+
+    let attrs = LispAttrs::parse(&input.attrs)?;
+
+    if attrs.skip {
+        return Err(Error::new_spanned(ident, "only fields can be skipped"));
+    }
+
+    let name = attrs
+        .name
+        .unwrap_or_else(|| kebab_case(&ident.unraw().to_string()));
+    let constructor = format!("make-{}", name);
+    let predicate = format!("{}?", name);
+
+    let mut inits = Vec::new();
+    let mut getters = Vec::new();
+
+    for field in fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let attrs = LispAttrs::parse(&field.attrs)?;
+
+        if attrs.skip {
+            inits.push(quote!(#field_ident: ::core::default::Default::default()));
+            continue;
+        }
+
+        let getter = format!(
+            "{}-{}",
+            name,
+            attrs
+                .name
+                .unwrap_or_else(|| field_ident.unraw().to_string().replace('_', "-"))
+        );
+
+        inits.push(quote!(#field_ident: <#ty as ::lithia::args::Arg>::take(&mut args)?));
+        getters.push(quote! {
+            .add_func(#getter, |object: Rc<Object>| {
+                expect(&object).map(|value| ::core::clone::Clone::clone(&value.#field_ident))
+            })?
+        });
+    }
+
+    Ok(quote! {
+        impl ::lithia::args::IntoLisp for #ident {
+            fn into_lisp(self) -> ::lithia::rc::Rc<::lithia::object::Object> {
+                ::lithia::rc::Rc::new(::lithia::object::Object::new_rust_type(self))
+            }
+        }
+
+        impl ::lithia::lisp::Register for #ident {
+            fn register(
+                builder: ::lithia::lisp::LispBuilder,
+            ) -> ::core::result::Result<::lithia::lisp::LispBuilder, ::lithia::errors::LispError> {
+                use ::lithia::{
+                    args::{Args, IntoLisp},
+                    errors::{RustFuncError, RustFuncResult},
+                    lisp::Lisp,
+                    object::Object,
+                    rc::Rc,
+                };
+
+                fn expect(object: &Object) -> ::core::result::Result<&#ident, RustFuncError> {
+                    object
+                        .downcast_ref::<#ident>()
+                        .map_err(RustFuncError::new_args_error)
+                }
+
+                // Fields are taken one at a time, as there can be more than typed functions take
+                let construct = |lisp: &mut Lisp, args: Rc<Object>| -> RustFuncResult {
+                    let mut args = Args::new(lisp, args);
+                    let value = #ident { #(#inits,)* };
+
+                    args.finish()?;
+
+                    Ok(value.into_lisp())
+                };
+
+                Ok(builder
+                    .add_type_name::<#ident>(#name)
+                    .add_func(#constructor, construct)?
+                    .add_func(#predicate, |object: Rc<Object>| expect(&object).is_ok())?
+                    #(#getters)*)
+            }
+        }
+    })
+}
+
+// Options given as `#[lisp(...)]`
+#[derive(Default)]
+struct LispAttrs {
+    name: Option<String>,
+    skip: bool,
+}
+
+impl LispAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("lisp")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    parsed.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `name = \"...\"` or `skip`"))
+                }
+            })?;
+        }
+
+        Ok(parsed)
+    }
+}
+
+// Lisp names are lowercase with dashes between words, so `HttpRequest` becomes `http-request`
+fn kebab_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut kebab = String::new();
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let after_lower = chars[i - 1].is_lowercase() || chars[i - 1].is_numeric();
+            let before_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+
+            if after_lower || (chars[i - 1].is_uppercase() && before_lower) {
+                kebab.push('-');
+            }
+        }
+
+        kebab.extend(c.to_lowercase());
+    }
+
+    kebab
+}
@@ -0,0 +1,413 @@
+// Converting between objects and rust values, so rust functions can take typed arguments instead of
+// matching on the list of objects they are given
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    errors::*,
+    lisp::Lisp,
+    object::{Closure, Object},
+    rc::{Rc, Shareable},
+    symbol::Symbol,
+};
+
+// A rust value which can be taken from an object
+pub trait FromLisp: Sized {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError>;
+
+    // The value of an argument which wasn't given, which is an error unless it is optional
+    fn from_missing() -> Result<Self, ArgumentsError> {
+        Err(ArgumentsError::NotEnough)
+    }
+}
+
+// A rust value which can be given to lisp as an object
+pub trait IntoLisp {
+    fn into_lisp(self) -> Rc<Object>;
+}
+
+impl FromLisp for Rc<Object> {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        Ok(Rc::clone(object))
+    }
+}
+
+impl IntoLisp for Rc<Object> {
+    fn into_lisp(self) -> Rc<Object> {
+        self
+    }
+}
+
+impl IntoLisp for Object {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(self)
+    }
+}
+
+impl FromLisp for f32 {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match **object {
+            Object::Number(n) => Ok(n),
+            _ => Err(ArgumentsError::WrongType),
+        }
+    }
+}
+
+impl IntoLisp for f32 {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::Number(self))
+    }
+}
+
+// Whole numbers which aren't negative, such as indexes
+impl FromLisp for usize {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match **object {
+            Object::Number(n) if n >= 0.0 && n as usize as f32 == n => Ok(n as usize),
+            _ => Err(ArgumentsError::WrongType),
+        }
+    }
+}
+
+impl IntoLisp for usize {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::Number(self as f32))
+    }
+}
+
+impl FromLisp for char {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match **object {
+            Object::Character(c) => Ok(c),
+            _ => Err(ArgumentsError::WrongType),
+        }
+    }
+}
+
+impl IntoLisp for char {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::Character(self))
+    }
+}
+
+// Anything but nil is true
+impl FromLisp for bool {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        Ok(!matches!(**object, Object::Nil))
+    }
+}
+
+impl IntoLisp for bool {
+    fn into_lisp(self) -> Rc<Object> {
+        match self {
+            true => Rc::new(Object::True),
+            false => Rc::new(Object::Nil),
+        }
+    }
+}
+
+impl FromLisp for String {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        object
+            .pair_list_to_string()
+            .map_err(|_| ArgumentsError::WrongType)
+    }
+}
+
+impl IntoLisp for String {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::string_to_lisp_string(&self))
+    }
+}
+
+impl IntoLisp for &str {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::string_to_lisp_string(self))
+    }
+}
+
+impl FromLisp for Symbol {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match &**object {
+            Object::Symbol(s) => Ok(s.clone()),
+            _ => Err(ArgumentsError::WrongType),
+        }
+    }
+}
+
+impl IntoLisp for Symbol {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::Symbol(self))
+    }
+}
+
+// Lists
+impl<T: FromLisp> FromLisp for Vec<T> {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        object
+            .pair_list_to_vec()?
+            .iter()
+            .map(T::from_lisp)
+            .collect()
+    }
+}
+
+impl<T: IntoLisp> IntoLisp for Vec<T> {
+    fn into_lisp(self) -> Rc<Object> {
+        let objects = self.into_iter().map(IntoLisp::into_lisp).collect();
+
+        Rc::new(Object::array_to_pair_list(objects))
+    }
+}
+
+// Nil, or an argument which wasn't given, is none
+impl<T: FromLisp> FromLisp for Option<T> {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match **object {
+            Object::Nil => Ok(None),
+            _ => T::from_lisp(object).map(Some),
+        }
+    }
+
+    fn from_missing() -> Result<Self, ArgumentsError> {
+        Ok(None)
+    }
+}
+
+impl<T: IntoLisp> IntoLisp for Option<T> {
+    fn into_lisp(self) -> Rc<Object> {
+        match self {
+            Some(v) => v.into_lisp(),
+            None => Rc::new(Object::Nil),
+        }
+    }
+}
+
+impl IntoLisp for () {
+    fn into_lisp(self) -> Rc<Object> {
+        Rc::new(Object::Nil)
+    }
+}
+
+// The arguments rust gives a function it calls, as a tuple of values or a list of objects
+pub trait IntoArgs {
+    fn into_args(self) -> Vec<Rc<Object>>;
+}
+
+impl IntoArgs for Vec<Rc<Object>> {
+    fn into_args(self) -> Vec<Rc<Object>> {
+        self
+    }
+}
+
+macro_rules! call_args {
+    ($($param:ident),*) => {
+        impl<$($param: IntoLisp),*> IntoArgs for ($($param,)*) {
+            #[allow(non_snake_case)]
+            fn into_args(self) -> Vec<Rc<Object>> {
+                let ($($param,)*) = self;
+
+                alloc::vec![$($param.into_lisp()),*]
+            }
+        }
+    };
+}
+
+call_args!();
+call_args!(A);
+call_args!(A, B);
+call_args!(A, B, C);
+call_args!(A, B, C, D);
+call_args!(A, B, C, D, E);
+call_args!(A, B, C, D, E, F);
+
+// The arguments given to a rust function, evaluated as they are taken
+pub struct Args<'a> {
+    lisp: &'a mut Lisp,
+    list: Rc<Object>,
+}
+
+impl<'a> Args<'a> {
+    pub fn new(lisp: &'a mut Lisp, list: Rc<Object>) -> Self {
+        Self { lisp, list }
+    }
+
+    pub fn lisp(&mut self) -> &mut Lisp {
+        self.lisp
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !matches!(*self.list, Object::Pair(_, _))
+    }
+
+    // The next argument as it was given, without evaluating it
+    pub fn next_form(&mut self) -> Result<Option<Rc<Object>>, RustFuncError> {
+        let (first, rest) = match &*self.list {
+            Object::Pair(a, b) => (a.get(), b.get()),
+            Object::Nil => return Ok(None),
+            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+        };
+
+        self.list = rest;
+
+        Ok(Some(first))
+    }
+
+    // The next argument, evaluated
+    pub fn next_value(&mut self) -> Result<Option<Rc<Object>>, RustFuncError> {
+        match self.next_form()? {
+            Some(o) => Ok(Some(self.lisp.eval_object(o)?)),
+            None => Ok(None),
+        }
+    }
+
+    // Check every argument has been taken
+    pub fn finish(self) -> Result<(), RustFuncError> {
+        match *self.list {
+            Object::Nil => Ok(()),
+            Object::Pair(_, _) => Err(RustFuncError::new_args_error(ArgumentsError::TooMany)),
+            _ => Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+        }
+    }
+}
+
+// A parameter of a rust function, taking what it needs from the arguments given
+pub trait Arg: Sized {
+    // Whether this is taken by evaluating the arguments in order, so compiled code can evaluate
+    // them before the function is called
+    const EVALUATED: bool = false;
+
+    fn take(args: &mut Args) -> Result<Self, RustFuncError>;
+}
+
+impl<T: FromLisp> Arg for T {
+    const EVALUATED: bool = true;
+
+    fn take(args: &mut Args) -> Result<Self, RustFuncError> {
+        match args.next_value()? {
+            Some(o) => T::from_lisp(&o),
+            None => T::from_missing(),
+        }
+        .map_err(RustFuncError::new_args_error)
+    }
+}
+
+// An argument which is taken without being evaluated, for functions which decide when and if to
+// evaluate their arguments themselves
+pub struct Form<T = Rc<Object>>(pub T);
+
+impl<T: FromLisp> Arg for Form<T> {
+    fn take(args: &mut Args) -> Result<Self, RustFuncError> {
+        match args.next_form()? {
+            Some(o) => T::from_lisp(&o),
+            None => T::from_missing(),
+        }
+        .map(Form)
+        .map_err(RustFuncError::new_args_error)
+    }
+}
+
+// Every argument left, which may be none
+pub struct Rest<T>(pub Vec<T>);
+
+impl<T: Arg> Arg for Rest<T> {
+    const EVALUATED: bool = T::EVALUATED;
+
+    fn take(args: &mut Args) -> Result<Self, RustFuncError> {
+        let mut taken = Vec::new();
+
+        while !args.is_empty() {
+            taken.push(T::take(args)?);
+        }
+
+        Ok(Rest(taken))
+    }
+}
+
+// Every parameter of a rust function, as a tuple
+pub trait FromArgs: Sized {
+    fn from_args(args: &mut Args) -> Result<Self, RustFuncError>;
+}
+
+// What a typed rust function returns, which can be a value or a result of one
+pub trait IntoFuncResult {
+    fn into_func_result(self) -> RustFuncResult;
+}
+
+impl<T: IntoLisp> IntoFuncResult for T {
+    fn into_func_result(self) -> RustFuncResult {
+        Ok(self.into_lisp())
+    }
+}
+
+impl<T: IntoLisp, E: Into<RustFuncError>> IntoFuncResult for Result<T, E> {
+    fn into_func_result(self) -> RustFuncResult {
+        self.map(IntoLisp::into_lisp).map_err(Into::into)
+    }
+}
+
+// Rust functions which can be added to lisp. Those given the interpreter and their arguments
+// unevaluated are used as they are, and those taking typed parameters have their arguments
+// evaluated and converted for them. The marker only tells the two apart
+pub trait IntoBuiltin<Marker> {
+    // Whether every argument is evaluated, in order, before the function does anything else
+    const EVALUATED: bool = false;
+
+    fn into_builtin(self) -> Rc<dyn Closure>;
+}
+
+pub struct Unevaluated;
+
+impl<F: Closure + 'static> IntoBuiltin<Unevaluated> for F {
+    fn into_builtin(self) -> Rc<dyn Closure> {
+        Rc::new(self)
+    }
+}
+
+macro_rules! typed_functions {
+    ($($param:ident),*) => {
+        impl<$($param: Arg),*> FromArgs for ($($param,)*) {
+            #[allow(unused_variables)]
+            fn from_args(args: &mut Args) -> Result<Self, RustFuncError> {
+                Ok(($($param::take(args)?,)*))
+            }
+        }
+
+        impl<Func, Ret, $($param: Arg),*> IntoBuiltin<($($param,)*)> for Func
+        where
+            Func: Fn($($param),*) -> Ret + Shareable + 'static,
+            Ret: IntoFuncResult,
+        {
+            const EVALUATED: bool = true $(&& $param::EVALUATED)*;
+
+            fn into_builtin(self) -> Rc<dyn Closure> {
+                Rc::new(move |lisp: &mut Lisp, args: Rc<Object>| {
+                    #[allow(non_snake_case)]
+                    let ($($param,)*) = lisp.parse_args::<($($param,)*)>(args)?;
+
+                    self($($param),*).into_func_result()
+                })
+            }
+        }
+    };
+}
+
+typed_functions!();
+typed_functions!(A);
+typed_functions!(A, B);
+typed_functions!(A, B, C);
+typed_functions!(A, B, C, D);
+typed_functions!(A, B, C, D, E);
+typed_functions!(A, B, C, D, E, F);
+
+impl Lisp {
+    // Take the arguments given to a rust function, checking there are neither too few nor too many
+    pub fn parse_args<T: FromArgs>(&mut self, args: Rc<Object>) -> Result<T, RustFuncError> {
+        let mut args = Args::new(self, args);
+        let parsed = T::from_args(&mut args)?;
+
+        args.finish()?;
+
+        Ok(parsed)
+    }
+}
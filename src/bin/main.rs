@@ -4,7 +4,11 @@ use std::{env, fs};
 use lithia::lisp::LispBuilder;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // Run with the bytecode compiler rather than walking objects
+    let bytecode = args.iter().any(|a| a == "--bytecode");
+    args.retain(|a| a != "--bytecode");
 
     let code = if args.len() > 1 {
         fs::read_to_string(&args[1]).unwrap()
@@ -12,7 +16,11 @@ fn main() {
         "(while t (print (eval (read))))".to_string()
     };
 
-    let mut lisp = LispBuilder::new().add_default_envs().unwrap().build();
+    let mut lisp = LispBuilder::new()
+        .add_default_envs()
+        .unwrap()
+        .use_bytecode(bytecode)
+        .build();
 
     let ret = lisp.eval(&code);
 
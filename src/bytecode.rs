@@ -0,0 +1,392 @@
+// Compiling objects into bytecode for a stack machine, an alternative to walking the objects
+// each time they are evaluated
+
+use alloc::vec::Vec;
+
+use crate::{
+    errors::*,
+    lisp::Lisp,
+    object::Object,
+    rc::Rc,
+    symbol::{Symbol, SymbolMap},
+};
+
+// Builtins which are compiled into bytecode directly, rather than being called
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SpecialForm {
+    Quote,
+    If,
+    While,
+    Set,
+}
+
+// Keyed by the address of the builtin, which is kept alive by being stored alongside
+pub(crate) type SpecialForms = SymbolMap<(Rc<Object>, SpecialForm)>;
+
+#[derive(Debug)]
+enum Op {
+    // Push an object as it is
+    Const(Rc<Object>),
+    // Push the value of a variable
+    Load(Symbol),
+    // Push a parameter of the function being run, by where it is in the frame
+    LoadLocal(usize, Symbol),
+    // Pop a value into a variable
+    Set(Symbol),
+    SetLocal(usize, Symbol),
+    Pop,
+    Jump(usize),
+    JumpIfNil(usize),
+    ScopeStart,
+    ScopeEnd,
+    // Continue if the top of the stack is this object, otherwise jump leaving it there
+    Guard(Rc<Object>, usize),
+    // Call the function on the top of the stack if it is a builtin, which evaluates its own
+    // arguments, jumping past them. Otherwise continue on to evaluate them, as also happens for
+    // the builtin given, which was found to evaluate all its arguments when this was compiled
+    CallBuiltin(Rc<Object>, Option<Rc<Object>>, usize),
+    // Call the function below the given number of arguments
+    Call(usize),
+    // Arguments which can't be evaluated
+    ArgsError(ArgumentsError),
+}
+
+// Compiled objects, evaluated in order with the value of the last one left at the end
+#[derive(Debug)]
+pub struct Code {
+    ops: Vec<Op>,
+}
+
+impl Code {
+    pub(crate) fn compile(lisp: &Lisp, objects: &[Rc<Object>]) -> Self {
+        Self::compile_function(lisp, &[], objects)
+    }
+
+    // Compile the body of a function, whose parameters are found by where they are in its frame
+    // rather than by name
+    pub(crate) fn compile_function(lisp: &Lisp, params: &[Symbol], body: &[Rc<Object>]) -> Self {
+        let mut compiler = Compiler {
+            lisp,
+            params,
+            ops: Vec::new(),
+        };
+
+        compiler.compile_body(body);
+
+        Self { ops: compiler.ops }
+    }
+}
+
+struct Compiler<'a> {
+    lisp: &'a Lisp,
+    params: &'a [Symbol],
+    ops: Vec<Op>,
+}
+
+impl Compiler<'_> {
+    fn compile_body(&mut self, objects: &[Rc<Object>]) {
+        match objects.split_last() {
+            Some((last, objects)) => {
+                for o in objects {
+                    self.compile_object(o);
+                    self.ops.push(Op::Pop);
+                }
+
+                self.compile_object(last);
+            }
+            None => self.ops.push(Op::Const(Rc::new(Object::Nil))),
+        }
+    }
+
+    fn compile_object(&mut self, object: &Rc<Object>) {
+        match &**object {
+            Object::Pair(f, _) => {
+                let head = f.get();
+
+                match &*head {
+                    // Strings evaluate to themselves
+                    Object::Character(_) => self.ops.push(Op::Const(Rc::clone(object))),
+                    Object::Symbol(s) => match self.lisp.special_form(s) {
+                        Some((expected, form)) => self.compile_special(object, s, expected, form),
+                        None => {
+                            // Builtins are quicker to look up variables and constants themselves,
+                            // so only calls within the arguments are worth compiling for them
+                            let evaluated = match has_calls(object) {
+                                true => self.lisp.evaluated_builtin(s),
+                                false => None,
+                            };
+
+                            self.compile_load(s);
+                            self.compile_call(object, evaluated);
+                        }
+                    },
+                    _ => {
+                        self.compile_object(&head);
+                        self.compile_call(object, None);
+                    }
+                }
+            }
+            Object::Symbol(s) => self.compile_load(s),
+            Object::Quoted(o) => self.ops.push(Op::Const(Rc::clone(o))),
+            _ => self.ops.push(Op::Const(Rc::clone(object))),
+        }
+    }
+
+    // Parameters are always the first locals of a function, so can be found without their names
+    fn param(&self, symbol: &Symbol) -> Option<usize> {
+        self.params.iter().rposition(|p| p == symbol)
+    }
+
+    fn compile_load(&mut self, symbol: &Symbol) {
+        match self.param(symbol) {
+            Some(i) => self.ops.push(Op::LoadLocal(i, symbol.clone())),
+            None => self.ops.push(Op::Load(symbol.clone())),
+        }
+    }
+
+    // Call whatever function was pushed, the arguments only being evaluated if it wants them to be
+    fn compile_call(&mut self, form: &Rc<Object>, evaluated: Option<Rc<Object>>) {
+        let builtin = self.ops.len();
+        self.ops.push(Op::CallBuiltin(Rc::clone(form), None, 0));
+
+        match form_args(form) {
+            Ok(args) => {
+                for a in &args {
+                    self.compile_object(a);
+                }
+
+                self.ops.push(Op::Call(args.len()));
+            }
+            Err(e) => self.ops.push(Op::ArgsError(e)),
+        }
+
+        let end = self.ops.len();
+        self.ops[builtin] = Op::CallBuiltin(Rc::clone(form), evaluated, end);
+    }
+
+    // Builtins which can be compiled directly, as long as the symbol still refers to them when run
+    fn compile_special(
+        &mut self,
+        form: &Rc<Object>,
+        symbol: &Symbol,
+        expected: Rc<Object>,
+        special: SpecialForm,
+    ) {
+        let args = form_args(form).unwrap_or_default();
+
+        let inline = match special {
+            SpecialForm::Quote => args.len() == 1,
+            SpecialForm::If => args.len() == 2 || args.len() == 3,
+            SpecialForm::While => !args.is_empty(),
+            SpecialForm::Set => args.len() == 2 && matches!(*args[0], Object::Symbol(_)),
+        };
+
+        self.compile_load(symbol);
+
+        if !inline {
+            self.compile_call(form, None);
+            return;
+        }
+
+        let guard = self.ops.len();
+        self.ops.push(Op::Guard(Rc::clone(&expected), 0));
+
+        match special {
+            SpecialForm::Quote => self.ops.push(Op::Const(Rc::clone(&args[0]))),
+            SpecialForm::If => {
+                self.ops.push(Op::ScopeStart);
+                self.compile_object(&args[0]);
+
+                let otherwise = self.ops.len();
+                self.ops.push(Op::JumpIfNil(0));
+                self.compile_object(&args[1]);
+
+                let end = self.ops.len();
+                self.ops.push(Op::Jump(0));
+                self.ops[otherwise] = Op::JumpIfNil(self.ops.len());
+
+                match args.get(2) {
+                    Some(a) => self.compile_object(a),
+                    None => self.ops.push(Op::Const(Rc::new(Object::Nil))),
+                }
+
+                self.ops[end] = Op::Jump(self.ops.len());
+                self.ops.push(Op::ScopeEnd);
+            }
+            SpecialForm::While => {
+                self.ops.push(Op::ScopeStart);
+
+                let start = self.ops.len();
+                self.compile_object(&args[0]);
+
+                let exit = self.ops.len();
+                self.ops.push(Op::JumpIfNil(0));
+
+                for a in &args[1..] {
+                    self.compile_object(a);
+                    self.ops.push(Op::Pop);
+                }
+
+                self.ops.push(Op::Jump(start));
+                self.ops[exit] = Op::JumpIfNil(self.ops.len());
+                self.ops.push(Op::ScopeEnd);
+                self.ops.push(Op::Const(Rc::new(Object::Nil)));
+            }
+            SpecialForm::Set => {
+                self.compile_object(&args[1]);
+
+                if let Object::Symbol(s) = &*args[0] {
+                    match self.param(s) {
+                        Some(i) => self.ops.push(Op::SetLocal(i, s.clone())),
+                        None => self.ops.push(Op::Set(s.clone())),
+                    }
+                }
+
+                self.ops.push(Op::Const(Rc::new(Object::Nil)));
+            }
+        }
+
+        // If the symbol has been changed, call whatever it is now instead
+        let end = self.ops.len();
+        self.ops.push(Op::Jump(0));
+        self.ops[guard] = Op::Guard(expected, self.ops.len());
+
+        self.compile_call(form, None);
+        self.ops[end] = Op::Jump(self.ops.len());
+    }
+}
+
+// Whether any argument of a function call is a call itself
+fn has_calls(form: &Object) -> bool {
+    form_args(form).is_ok_and(|args| {
+        args.iter().any(|a| match &**a {
+            Object::Pair(f, _) => !matches!(*f.get(), Object::Character(_)),
+            _ => false,
+        })
+    })
+}
+
+// The arguments of a function call
+fn form_args(form: &Object) -> Result<Vec<Rc<Object>>, ArgumentsError> {
+    match form {
+        Object::Pair(_, a) => a.get().pair_list_to_vec().map_err(|e| match e {
+            ArgumentsError::WrongType => ArgumentsError::DottedPair,
+            e => e,
+        }),
+        _ => Ok(Vec::new()),
+    }
+}
+
+impl Lisp {
+    // Run compiled objects, giving the value of the last one
+    pub(crate) fn run(&mut self, code: &Code) -> LispResult {
+        // The stack is shared with the code that called this, so leave it as it was found. So are
+        // scopes, which are left open when an error stops the code before it ends them
+        let base = self.stack.len();
+        let depth = self.scope_depth();
+        let ret = self.run_ops(code, base);
+
+        self.stack.truncate(base);
+        self.scope_end_to(depth);
+
+        ret
+    }
+
+    fn run_ops(&mut self, code: &Code, base: usize) -> LispResult {
+        let mut pc = 0;
+
+        while let Some(op) = code.ops.get(pc) {
+            pc += 1;
+
+            match op {
+                Op::Const(o) => self.stack.push(Rc::clone(o)),
+                Op::Load(s) => self.stack.push(self.eval_symbol(s)?),
+                Op::LoadLocal(i, s) => self.stack.push(self.eval_local(*i, s)?),
+                Op::Set(s) => {
+                    let value = self.stack.pop().unwrap();
+
+                    self.set_var(s, value)?;
+                }
+                Op::SetLocal(i, s) => {
+                    let value = self.stack.pop().unwrap();
+
+                    self.set_local(*i, s, value)?;
+                }
+                Op::Pop => {
+                    self.stack.pop();
+                }
+                Op::Jump(t) => pc = *t,
+                Op::JumpIfNil(t) => {
+                    if matches!(*self.stack.pop().unwrap(), Object::Nil) {
+                        pc = *t
+                    }
+                }
+                Op::ScopeStart => self.scope_create(),
+                Op::ScopeEnd => self.scope_end(),
+                Op::Guard(expected, t) => {
+                    if Rc::ptr_eq(self.stack.last().unwrap(), expected) {
+                        self.stack.pop();
+                    } else {
+                        pc = *t
+                    }
+                }
+                Op::CallBuiltin(form, evaluated, t) => match &**self.stack.last().unwrap() {
+                    _ if evaluated
+                        .as_ref()
+                        .is_some_and(|e| Rc::ptr_eq(e, self.stack.last().unwrap())) => {}
+                    Object::RustFunc(_) | Object::RustClosure(_) => {
+                        let function = self.stack.pop().unwrap();
+                        let args = match &**form {
+                            Object::Pair(_, a) => a.get(),
+                            _ => Rc::new(Object::Nil),
+                        };
+
+                        let ret = self.call_builtin(&function, args)?;
+
+                        self.stack.push(ret);
+                        pc = *t;
+                    }
+                    Object::LispFunc(_) => (),
+                    Object::Character(_) => {
+                        self.stack.pop();
+                        self.stack.push(Rc::clone(form));
+                        pc = *t;
+                    }
+                    _ => {
+                        return Err(LispError::new(
+                            LispErrorKind::Eval,
+                            EvalError::NonFunction(Rc::clone(form)),
+                        ))
+                    }
+                },
+                Op::Call(n) => {
+                    let args = self.stack.len() - n;
+                    let function = Rc::clone(&self.stack[args - 1]);
+
+                    // Lisp functions take their arguments straight off the stack
+                    let ret = match &*function {
+                        Object::LispFunc(func) => self.call_lisp_func(func, args)?,
+                        _ => {
+                            let args = self.stack.split_off(args);
+                            self.call_function(&function, args)?
+                        }
+                    };
+
+                    self.stack.pop();
+                    self.stack.push(ret);
+                }
+                Op::ArgsError(e) => {
+                    return Err(LispError::new(
+                        LispErrorKind::RustFunc,
+                        RustFuncError::new_args_error(e.clone()),
+                    ))
+                }
+            }
+        }
+
+        match self.stack.len() > base {
+            true => Ok(self.stack.pop().unwrap()),
+            false => Ok(Rc::new(Object::Nil)),
+        }
+    }
+}
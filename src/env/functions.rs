@@ -0,0 +1,229 @@
+// Functions which take other functions as arguments
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    args::Rest,
+    errors::*,
+    lisp::{Lisp, LispBuilder},
+    object::Object,
+    rc::Rc,
+};
+
+impl LispBuilder {
+    pub fn add_env_functions(self) -> Result<Self, LispError> {
+        self.add_evaluated_func("apply", apply)?
+            .add_evaluated_func("funcall", funcall)?
+            .add_evaluated_func("map", map)?
+            .add_evaluated_func("filter", filter)?
+            .add_evaluated_func("reduce", reduce)?
+            .add_evaluated_func("fold-left", fold_left)?
+            .add_evaluated_func("fold-right", fold_right)?
+            .add_evaluated_func("for-each", for_each)?
+            .add_evaluated_func("any", any)?
+            .add_evaluated_func("every", every)?
+            .add_evaluated_func("find", find)?
+            .add_evaluated_func("sort", sort)
+    }
+}
+
+fn call(lisp: &mut Lisp, function: &Rc<Object>, args: Vec<Rc<Object>>) -> RustFuncResult {
+    Ok(lisp.call_function(function, args)?)
+}
+
+// Call a function with arguments, the last of which is a list of further arguments
+fn apply(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, first, Rest(mut args)): (Rc<Object>, Rc<Object>, Rest<Rc<Object>>) =
+        lisp.parse_args(arg)?;
+
+    args.insert(0, first);
+
+    let rest = args.pop().unwrap().pair_list_to_vec();
+    args.extend(rest.map_err(RustFuncError::new_args_error)?);
+
+    call(lisp, &function, args)
+}
+
+// Call a function with the rest of the arguments
+fn funcall(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, Rest(args)): (Rc<Object>, Rest<Rc<Object>>) = lisp.parse_args(arg)?;
+
+    call(lisp, &function, args)
+}
+
+// Call a function with an item from each list in turn, until the shortest list runs out
+fn map_lists(lisp: &mut Lisp, arg: Rc<Object>) -> Result<Vec<Rc<Object>>, RustFuncError> {
+    let (function, Rest(lists)): (Rc<Object>, Rest<Vec<Rc<Object>>>) = lisp.parse_args(arg)?;
+
+    if lists.is_empty() {
+        return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough));
+    }
+
+    let len = lists.iter().map(Vec::len).min().unwrap_or(0);
+
+    (0..len)
+        .map(|i| {
+            let items = lists.iter().map(|l| Rc::clone(&l[i])).collect();
+
+            call(lisp, &function, items)
+        })
+        .collect()
+}
+
+fn map(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let results = map_lists(lisp, arg)?;
+
+    Ok(Rc::new(Object::array_to_pair_list(results)))
+}
+
+// Like map, but only for the side effects of the function
+fn for_each(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    map_lists(lisp, arg)?;
+
+    Ok(Rc::new(Object::Nil))
+}
+
+// Keep the items of a list that the function returns true for
+fn filter(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, items): (Rc<Object>, Vec<Rc<Object>>) = lisp.parse_args(arg)?;
+    let mut kept = Vec::new();
+
+    for o in items {
+        if *call(lisp, &function, vec![Rc::clone(&o)])? != Object::Nil {
+            kept.push(o);
+        }
+    }
+
+    Ok(Rc::new(Object::array_to_pair_list(kept)))
+}
+
+// Combine the items of a list from the left, starting from the first item or an initial value,
+// which may be nil so is taken as the rest of the arguments rather than an optional one
+fn reduce(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, items, Rest(init)): (Rc<Object>, Vec<Rc<Object>>, Rest<Rc<Object>>) =
+        lisp.parse_args(arg)?;
+
+    if init.len() > 1 {
+        return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
+    }
+
+    let mut items = items.into_iter();
+
+    let mut ret = match init.into_iter().next().or_else(|| items.next()) {
+        Some(i) => i,
+        None => return Ok(Rc::new(Object::Nil)),
+    };
+
+    for o in items {
+        ret = call(lisp, &function, vec![ret, o])?;
+    }
+
+    Ok(ret)
+}
+
+// (f (f init a) b)
+fn fold_left(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, mut ret, items): (Rc<Object>, Rc<Object>, Vec<Rc<Object>>) =
+        lisp.parse_args(arg)?;
+
+    for o in items {
+        ret = call(lisp, &function, vec![ret, o])?;
+    }
+
+    Ok(ret)
+}
+
+// (f a (f b init))
+fn fold_right(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, mut ret, items): (Rc<Object>, Rc<Object>, Vec<Rc<Object>>) =
+        lisp.parse_args(arg)?;
+
+    for o in items.into_iter().rev() {
+        ret = call(lisp, &function, vec![o, ret])?;
+    }
+
+    Ok(ret)
+}
+
+// Give the first true result of the function on the items of a list, or nil
+fn any(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, items): (Rc<Object>, Vec<Rc<Object>>) = lisp.parse_args(arg)?;
+
+    for o in items {
+        let ret = call(lisp, &function, vec![o])?;
+
+        if *ret != Object::Nil {
+            return Ok(ret);
+        }
+    }
+
+    Ok(Rc::new(Object::Nil))
+}
+
+// Check the function is true for every item of a list, giving the last result
+fn every(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, items): (Rc<Object>, Vec<Rc<Object>>) = lisp.parse_args(arg)?;
+    let mut ret = Rc::new(Object::True);
+
+    for o in items {
+        ret = call(lisp, &function, vec![o])?;
+
+        if *ret == Object::Nil {
+            break;
+        }
+    }
+
+    Ok(ret)
+}
+
+// Give the first item of a list that the function returns true for, or nil
+fn find(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, items): (Rc<Object>, Vec<Rc<Object>>) = lisp.parse_args(arg)?;
+
+    for o in items {
+        if *call(lisp, &function, vec![Rc::clone(&o)])? != Object::Nil {
+            return Ok(o);
+        }
+    }
+
+    Ok(Rc::new(Object::Nil))
+}
+
+// Stable merge sort, where less returns true if its first argument goes before its second
+fn merge_sort(
+    lisp: &mut Lisp,
+    less: &Rc<Object>,
+    mut items: Vec<Rc<Object>>,
+) -> Result<Vec<Rc<Object>>, RustFuncError> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let mut left = merge_sort(lisp, less, items)?.into_iter().peekable();
+    let mut right = merge_sort(lisp, less, right)?.into_iter().peekable();
+    let mut sorted = Vec::with_capacity(left.len() + right.len());
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // Only take from the right if it is strictly less, to keep equal items in order
+        if *call(lisp, less, vec![Rc::clone(r), Rc::clone(l)])? != Object::Nil {
+            sorted.push(right.next().unwrap());
+        } else {
+            sorted.push(left.next().unwrap());
+        }
+    }
+
+    sorted.extend(left);
+    sorted.extend(right);
+
+    Ok(sorted)
+}
+
+// Sort a list with a comparison function
+fn sort(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (items, less): (Vec<Rc<Object>>, Rc<Object>) = lisp.parse_args(arg)?;
+
+    let sorted = merge_sort(lisp, &less, items)?;
+
+    Ok(Rc::new(Object::array_to_pair_list(sorted)))
+}
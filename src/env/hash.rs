@@ -0,0 +1,145 @@
+// Hash tables, which map keys to values using equal? to compare keys
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    errors::*,
+    lisp::{Lisp, LispBuilder},
+    object::{HashTable, Object},
+    rc::{Rc, RefCell},
+};
+
+impl LispBuilder {
+    pub fn add_env_hash(self) -> Result<Self, LispError> {
+        self.add_func("make-hash-table", make_hash_table)?
+            .add_func("hash-ref", hash_ref)?
+            .add_evaluated_func("hash-set!", hash_set)?
+            .add_func("hash-remove!", hash_remove)?
+            .add_func("hash-keys", hash_keys)?
+            .add_func("hash-values", hash_values)?
+            .add_func("hash-count", hash_count)?
+            .add_func("hash->list", hash_to_list)?
+            .add_evaluated_func("hash-for-each", hash_for_each)
+    }
+}
+
+fn expect_table(object: &Object) -> Result<&RefCell<HashTable>, RustFuncError> {
+    match object {
+        Object::HashTable(t) => Ok(t),
+        _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+    }
+}
+
+// Create a hash table, optionally filled from a list of key value pairs
+fn make_hash_table(pairs: Option<Vec<Rc<Object>>>) -> RustFuncResult {
+    let mut table = HashTable::new();
+
+    for pair in pairs.unwrap_or_default() {
+        match &*pair {
+            Object::Pair(k, v) => {
+                let key = table.key(k.get()).map_err(RustFuncError::new_args_error)?;
+
+                table.insert(key, v.get())
+            }
+            _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+        };
+    }
+
+    Ok(Rc::new(Object::HashTable(RefCell::new(table))))
+}
+
+// Get the value for a key, or a default value (nil if not given) when the key isn't present
+fn hash_ref(table: Rc<Object>, key: Rc<Object>, default: Option<Rc<Object>>) -> RustFuncResult {
+    let table = expect_table(&table)?.borrow();
+    let value = table.lookup(key).map_err(RustFuncError::new_args_error)?;
+
+    Ok(match (value, default) {
+        (Some(v), _) => Rc::clone(v),
+        (None, Some(d)) => d,
+        (None, None) => Rc::new(Object::Nil),
+    })
+}
+
+// The key is found before the table is borrowed mutably, as comparing it to the keys already in
+// the table can need to borrow the table, if the key contains it
+fn hash_set(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (table, key, value): (Rc<Object>, Rc<Object>, Rc<Object>) = lisp.parse_args(arg)?;
+    let cell = expect_table(&table)?;
+    let key = cell
+        .borrow()
+        .key(key)
+        .map_err(RustFuncError::new_args_error)?;
+
+    cell.borrow_mut().insert(key, value);
+
+    lisp.track(&table);
+
+    Ok(Rc::new(Object::Nil))
+}
+
+// Remove a key, giving the value it had or nil
+fn hash_remove(table: Rc<Object>, key: Rc<Object>) -> Result<Option<Rc<Object>>, RustFuncError> {
+    let table = expect_table(&table)?;
+    let key = table
+        .borrow()
+        .key(key)
+        .map_err(RustFuncError::new_args_error)?;
+
+    let removed = table.borrow_mut().remove(&key);
+
+    Ok(removed)
+}
+
+fn hash_keys(table: Rc<Object>) -> Result<Vec<Rc<Object>>, RustFuncError> {
+    let keys = expect_table(&table)?
+        .borrow()
+        .iter()
+        .map(|(k, _)| Rc::clone(k))
+        .collect();
+
+    Ok(keys)
+}
+
+fn hash_values(table: Rc<Object>) -> Result<Vec<Rc<Object>>, RustFuncError> {
+    let values = expect_table(&table)?
+        .borrow()
+        .iter()
+        .map(|(_, v)| Rc::clone(v))
+        .collect();
+
+    Ok(values)
+}
+
+fn hash_count(table: Rc<Object>) -> Result<usize, RustFuncError> {
+    Ok(expect_table(&table)?.borrow().len())
+}
+
+fn entries(table: &RefCell<HashTable>) -> Vec<(Rc<Object>, Rc<Object>)> {
+    table
+        .borrow()
+        .iter()
+        .map(|(k, v)| (Rc::clone(k), Rc::clone(v)))
+        .collect()
+}
+
+// Get a list of key value pairs
+fn hash_to_list(table: Rc<Object>) -> Result<Vec<Object>, RustFuncError> {
+    let pairs = entries(expect_table(&table)?)
+        .into_iter()
+        .map(|(k, v)| Object::pair(k, v))
+        .collect();
+
+    Ok(pairs)
+}
+
+// Call a function with each key and value
+fn hash_for_each(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, table): (Rc<Object>, Rc<Object>) = lisp.parse_args(arg)?;
+
+    // Copied so the function can change the table while it is iterated over
+    for (k, v) in entries(expect_table(&table)?) {
+        lisp.call_function(&function, vec![k, v])?;
+    }
+
+    Ok(Rc::new(Object::Nil))
+}
@@ -0,0 +1,234 @@
+// Functions for building and taking apart lists
+
+use alloc::vec::Vec;
+
+use super::{Length, MAX_LENGTH};
+use crate::{
+    args::Rest,
+    errors::*,
+    lisp::{Lisp, LispBuilder},
+    object::Object,
+    rc::Rc,
+};
+
+impl LispBuilder {
+    pub fn add_env_lists(self) -> Result<Self, LispError> {
+        self.add_func("list", list)?
+            .add_func("length", length)?
+            .add_func("append", append)?
+            .add_func("reverse", reverse)?
+            .add_func("nth", nth)?
+            .add_func("nthcdr", nthcdr)?
+            .add_func("last", last)?
+            .add_func("member", member)?
+            .add_func("assoc", assoc)?
+            .add_func("list-copy", list_copy)?
+            .add_evaluated_func("set-car!", set_car)?
+            .add_evaluated_func("set-cdr!", set_cdr)?
+            .add_func("range", range)?
+            .add_func("iota", iota)?
+            .add_func("null?", null)?
+            .add_func("pair?", pair)?
+            .add_func("list?", is_list)
+    }
+}
+
+// Follow the rest of a list n times
+fn skip(list: &Rc<Object>, n: usize) -> RustFuncResult {
+    let mut cur_object = Rc::clone(list);
+
+    for _ in 0..n {
+        match &*Rc::clone(&cur_object) {
+            Object::Pair(_, b) => cur_object = b.get(),
+            Object::Nil => break,
+            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+        }
+    }
+
+    Ok(cur_object)
+}
+
+// Create a list from the arguments
+fn list(Rest(objects): Rest<Rc<Object>>) -> Object {
+    Object::array_to_pair_list(objects)
+}
+
+fn length(list: Vec<Rc<Object>>) -> usize {
+    list.len()
+}
+
+// Join lists together, the last argument becoming the end of the new list
+fn append(Rest(mut args): Rest<Rc<Object>>) -> RustFuncResult {
+    let tail = match args.pop() {
+        Some(t) => t,
+        None => return Ok(Rc::new(Object::Nil)),
+    };
+
+    let mut objects = Vec::new();
+    for a in args {
+        objects.extend(
+            a.pair_list_to_vec()
+                .map_err(RustFuncError::new_args_error)?,
+        );
+    }
+
+    let mut ret = tail;
+    for o in objects.into_iter().rev() {
+        ret = Rc::new(Object::pair(o, ret));
+    }
+
+    Ok(ret)
+}
+
+fn reverse(mut list: Vec<Rc<Object>>) -> Vec<Rc<Object>> {
+    list.reverse();
+
+    list
+}
+
+// Get the item at an index of a list, or nil if the list is too short
+fn nth(n: usize, list: Rc<Object>) -> RustFuncResult {
+    match &*skip(&list, n)? {
+        Object::Pair(a, _) => Ok(a.get()),
+        Object::Nil => Ok(Rc::new(Object::Nil)),
+        _ => Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+    }
+}
+
+// Get the list after skipping a number of items
+fn nthcdr(n: usize, list: Rc<Object>) -> RustFuncResult {
+    skip(&list, n)
+}
+
+// Get the last item of a list
+fn last(mut list: Vec<Rc<Object>>) -> Option<Rc<Object>> {
+    list.pop()
+}
+
+// Find an item in a list, giving the rest of the list from that item
+fn member(item: Rc<Object>, list: Rc<Object>) -> RustFuncResult {
+    let mut cur_object = Rc::clone(&list);
+
+    // Follows the list at half the speed, so it is caught up with if the list loops
+    let mut slow = list;
+    let mut steps = 0;
+
+    loop {
+        let next = match &*cur_object {
+            Object::Pair(a, b) => {
+                if *a.get() == *item {
+                    break Ok(cur_object);
+                }
+
+                b.get()
+            }
+            Object::Nil => break Ok(Rc::new(Object::Nil)),
+            _ => break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+        };
+
+        cur_object = next;
+        steps += 1;
+
+        if steps % 2 == 0 {
+            let next = match &*slow {
+                Object::Pair(_, b) => b.get(),
+                _ => unreachable!(),
+            };
+
+            slow = next;
+        }
+
+        // Every item has been checked by the time it is caught up with
+        if Rc::ptr_eq(&slow, &cur_object) {
+            break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair));
+        }
+    }
+}
+
+// Find the pair with the given key in a list of pairs
+fn assoc(key: Rc<Object>, list: Vec<Rc<Object>>) -> RustFuncResult {
+    for pair in list {
+        match &*pair {
+            Object::Pair(k, _) if *k.get() == *key => return Ok(pair),
+            Object::Pair(_, _) => (),
+            _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+        }
+    }
+
+    Ok(Rc::new(Object::Nil))
+}
+
+fn list_copy(list: Vec<Rc<Object>>) -> Vec<Rc<Object>> {
+    list
+}
+
+// Change the first half of a pair in place
+fn set_car(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pair, object): (Rc<Object>, Rc<Object>) = lisp.parse_args(arg)?;
+
+    match &*pair {
+        Object::Pair(a, _) => a.set(object),
+        _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+    }
+
+    lisp.track(&pair);
+
+    Ok(Rc::new(Object::Nil))
+}
+
+// Change the second half of a pair in place, which can make a list circular
+fn set_cdr(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pair, object): (Rc<Object>, Rc<Object>) = lisp.parse_args(arg)?;
+
+    match &*pair {
+        Object::Pair(_, b) => b.set(object),
+        _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+    }
+
+    lisp.track(&pair);
+
+    Ok(Rc::new(Object::Nil))
+}
+
+fn numbers_from(start: f32, step: f32, count: usize) -> Object {
+    let numbers = (0..count)
+        .map(|i| Rc::new(Object::Number(start + step * i as f32)))
+        .collect();
+
+    Object::array_to_pair_list(numbers)
+}
+
+// Numbers from start up to, but not including, end
+fn range(start: f32, end: f32, step: Option<f32>) -> Result<Object, RustFuncError> {
+    let step = step.unwrap_or(1.0);
+
+    if step == 0.0 || !step.is_finite() || !start.is_finite() || !end.is_finite() {
+        return Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange));
+    }
+
+    let count = ((end - start) / step).ceil().max(0.0);
+
+    if count > MAX_LENGTH as f32 {
+        return Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange));
+    }
+
+    Ok(numbers_from(start, step, count as usize))
+}
+
+// A number of numbers, counting from start, or zero
+fn iota(Length(count): Length, start: Option<f32>, step: Option<f32>) -> Object {
+    numbers_from(start.unwrap_or(0.0), step.unwrap_or(1.0), count)
+}
+
+fn null(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Nil)
+}
+
+fn pair(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Pair(_, _))
+}
+
+// Check for a list ending in nil
+fn is_list(object: Rc<Object>) -> bool {
+    object.pair_list_to_vec().is_ok()
+}
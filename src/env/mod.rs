@@ -1,7 +1,14 @@
-use crate::{errors::LispError, lisp::LispBuilder};
+use crate::{args::FromLisp, errors::*, lisp::LispBuilder, object::Object, rc::Rc};
 
+mod functions;
+mod hash;
+mod lists;
 mod maths;
+pub(crate) mod regex;
 mod std;
+mod string;
+mod types;
+mod vectors;
 
 #[cfg(feature = "std")]
 mod sys;
@@ -9,11 +16,45 @@ mod sys;
 impl LispBuilder {
     #[cfg(feature = "std")]
     pub fn add_default_envs(self) -> Result<Self, LispError> {
-        self.add_env_std()?.add_env_maths()?.add_env_sys()
+        self.add_env_std()?
+            .add_env_maths()?
+            .add_env_lists()?
+            .add_env_functions()?
+            .add_env_vectors()?
+            .add_env_hash()?
+            .add_env_types()?
+            .add_env_string()?
+            .add_env_regex()?
+            .add_env_sys()
     }
 
     #[cfg(not(feature = "std"))]
     pub fn add_default_envs(self) -> Result<Self, LispError> {
-        self.add_env_std()?.add_env_maths()
+        self.add_env_std()?
+            .add_env_maths()?
+            .add_env_lists()?
+            .add_env_functions()?
+            .add_env_vectors()?
+            .add_env_hash()?
+            .add_env_types()?
+            .add_env_string()?
+            .add_env_regex()
+    }
+}
+
+// The most items a builtin will make at once, so a mistaken size is an error rather than running
+// out of memory
+pub(crate) const MAX_LENGTH: usize = 1 << 24;
+
+// A number of items to make, which must be whole, positive and not too large
+pub(crate) struct Length(pub usize);
+
+impl FromLisp for Length {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match f32::from_lisp(object)? {
+            n if !(0.0..=MAX_LENGTH as f32).contains(&n) => Err(ArgumentsError::OutOfRange),
+            n if n as usize as f32 == n => Ok(Length(n as usize)),
+            _ => Err(ArgumentsError::WrongType),
+        }
     }
 }
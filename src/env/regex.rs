@@ -0,0 +1,207 @@
+// Regular expressions, compiled patterns are stored as rust types and cached
+
+use ::regex::{Captures, Regex};
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+
+use crate::{
+    args::{FromLisp, IntoLisp},
+    errors::*,
+    lisp::{Lisp, LispBuilder},
+    object::{HostType, Object},
+    rc::Rc,
+};
+
+impl LispBuilder {
+    pub fn add_env_regex(self) -> Result<Self, LispError> {
+        self.add_evaluated_func("regex", regex)?
+            .add_evaluated_func("regex-match?", regex_match)?
+            .add_evaluated_func("regex-find", regex_find)?
+            .add_evaluated_func("regex-find-all", regex_find_all)?
+            .add_evaluated_func("regex-replace", regex_replace)?
+            .add_evaluated_func("regex-split", regex_split)
+    }
+}
+
+// Patterns print as the string they were compiled from, and are equal if those are
+impl HostType for Regex {
+    fn type_name(&self) -> &str {
+        "regex"
+    }
+
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#<regex {:?}>", self.as_str())
+    }
+
+    fn equals(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+
+    fn host_hash(&self, mut state: &mut dyn Hasher) {
+        self.as_str().hash(&mut state)
+    }
+}
+
+// How many patterns given as strings are kept compiled
+const REGEX_CACHE_SIZE: usize = 64;
+
+// The patterns most recently compiled from strings, so one used in a loop is only compiled once,
+// without keeping every pattern a program has ever built
+#[derive(Default)]
+pub(crate) struct RegexCache {
+    // Each pattern with when it was last used
+    patterns: HashMap<String, (Rc<Object>, u64)>,
+    uses: u64,
+}
+
+impl RegexCache {
+    fn get(&mut self, pattern: &str) -> Option<Rc<Object>> {
+        self.uses += 1;
+        let uses = self.uses;
+
+        self.patterns.get_mut(pattern).map(|(regex, used)| {
+            *used = uses;
+            Rc::clone(regex)
+        })
+    }
+
+    // Add a pattern, forgetting the least recently used one if it is full
+    fn insert(&mut self, pattern: String, regex: Rc<Object>) {
+        if self.patterns.len() >= REGEX_CACHE_SIZE {
+            let oldest = self
+                .patterns
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(p, _)| p.clone());
+
+            if let Some(oldest) = oldest {
+                self.patterns.remove(&oldest);
+            }
+        }
+
+        self.uses += 1;
+        self.patterns.insert(pattern, (regex, self.uses));
+    }
+}
+
+// Get a compiled pattern from a string, compiling it only if it hasn't been used recently
+fn compile(lisp: &mut Lisp, pattern: &str) -> RustFuncResult {
+    if let Some(r) = lisp.regex_cache.borrow_mut().get(pattern) {
+        return Ok(r);
+    }
+
+    let regex = Regex::new(pattern).map_err(|e| {
+        RustFuncError::new_args_error(ArgumentsError::InvalidPattern(e.to_string()))
+    })?;
+    let regex = Rc::new(Object::new_host_type(regex));
+
+    lisp.regex_cache
+        .borrow_mut()
+        .insert(pattern.to_string(), Rc::clone(&regex));
+
+    Ok(regex)
+}
+
+// Patterns may be given either as strings or already compiled
+fn expect_regex(lisp: &mut Lisp, object: Rc<Object>) -> RustFuncResult {
+    match &*object {
+        Object::RustType(t) if t.is::<Regex>() => Ok(object),
+        _ => {
+            let pattern = String::from_lisp(&object).map_err(RustFuncError::new_args_error)?;
+
+            compile(lisp, &pattern)
+        }
+    }
+}
+
+fn as_regex(object: &Object) -> &Regex {
+    object.downcast_ref().unwrap()
+}
+
+// A match becomes a list of the whole match followed by each group, unmatched groups being nil
+fn captures_to_list(captures: Captures) -> Rc<Object> {
+    let groups = captures
+        .iter()
+        .map(|m| match m {
+            Some(m) => Rc::new(Object::string_to_lisp_string(m.as_str())),
+            None => Rc::new(Object::Nil),
+        })
+        .collect();
+
+    Rc::new(Object::array_to_pair_list(groups))
+}
+
+// Compile a pattern ahead of time
+fn regex(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pattern,): (Rc<Object>,) = lisp.parse_args(arg)?;
+
+    expect_regex(lisp, pattern)
+}
+
+// Check if a pattern matches anywhere within a string
+fn regex_match(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pattern, string): (Rc<Object>, String) = lisp.parse_args(arg)?;
+    let regex = expect_regex(lisp, pattern)?;
+
+    Ok(as_regex(&regex).is_match(&string).into_lisp())
+}
+
+// Find the first match in a string, or nil if there isn't one
+fn regex_find(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pattern, string): (Rc<Object>, String) = lisp.parse_args(arg)?;
+    let regex = expect_regex(lisp, pattern)?;
+
+    Ok(match as_regex(&regex).captures(&string) {
+        Some(c) => captures_to_list(c),
+        None => Rc::new(Object::Nil),
+    })
+}
+
+// Find every match in a string
+fn regex_find_all(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pattern, string): (Rc<Object>, String) = lisp.parse_args(arg)?;
+    let regex = expect_regex(lisp, pattern)?;
+
+    let matches = as_regex(&regex)
+        .captures_iter(&string)
+        .map(captures_to_list)
+        .collect();
+
+    Ok(Rc::new(Object::array_to_pair_list(matches)))
+}
+
+// Replace every match in a string, where $1 or ${name} in the replacement refer to groups
+fn regex_replace(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pattern, string, replacement): (Rc<Object>, String, String) = lisp.parse_args(arg)?;
+    let regex = expect_regex(lisp, pattern)?;
+
+    let string = as_regex(&regex).replace_all(&string, replacement.as_str());
+
+    Ok(Rc::new(Object::string_to_lisp_string(&string)))
+}
+
+// Split a string wherever the pattern matches
+fn regex_split(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (pattern, string): (Rc<Object>, String) = lisp.parse_args(arg)?;
+    let regex = expect_regex(lisp, pattern)?;
+
+    let parts: Vec<Rc<Object>> = as_regex(&regex)
+        .split(&string)
+        .map(|s| Rc::new(Object::string_to_lisp_string(s)))
+        .collect();
+
+    Ok(Rc::new(Object::array_to_pair_list(parts)))
+}
@@ -1,15 +1,15 @@
 // Defining the standard functions and variables that exist in the language
 
-use alloc::{
-    rc::Rc,
-    string::{String, ToString},
-    vec::Vec,
-};
+use alloc::vec::Vec;
 
 use crate::{
+    args::{Arg, Args, Form, FromLisp, IntoLisp, Rest},
+    bytecode::SpecialForm,
     errors::*,
     lisp::{Lisp, LispBuilder},
-    object::Object,
+    object::{LispFunction, Object},
+    rc::Rc,
+    symbol::Symbol,
 };
 
 impl LispBuilder {
@@ -19,580 +19,310 @@ impl LispBuilder {
             .add_var("f", Rc::new(Object::Nil))?
             .add_var("pi", Rc::new(Object::Number(core::f32::consts::PI)))?
             // Functions
-            .add_func("quote", quote)?
+            .add_compiled_form("quote", quote, SpecialForm::Quote)?
             .add_func("eval", eval)?
-            .add_func("while", lispwhile)?
-            .add_func("if", lispif)?
+            .add_compiled_form("while", lispwhile, SpecialForm::While)?
+            .add_compiled_form("if", lispif, SpecialForm::If)?
             .add_func("func", func)?
             .add_func("car", car)?
             .add_func("cdr", cdr)?
             .add_func("cons", cons)?
-            .add_func("=", set)?
+            .add_compiled_form("=", set, SpecialForm::Set)?
             .add_func("def", define)?
             .add_func("defunc", defunc)?
+            .add_evaluated_func("collect-garbage", collect_garbage)?
             // Math functions
-            .add_func("set", set)?
-            .add_func("add", add)?
-            .add_func("sub", minus)?
-            .add_func("mul", times)?
-            .add_func("div", divide)?
-            .add_func("mod", modulus)?
+            .add_compiled_form("set", set, SpecialForm::Set)?
+            .add_evaluated_func("add", add)?
+            .add_evaluated_func("sub", minus)?
+            .add_evaluated_func("mul", times)?
+            .add_evaluated_func("div", divide)?
+            .add_evaluated_func("mod", modulus)?
             .add_func("eq", equal)?
             .add_func("ne", notequal)?
+            .add_func("eq?", identical)?
+            .add_func("eqv?", equivalent)?
+            .add_func("equal?", equal)?
+            .add_evaluated_func("lt", less)?
+            .add_evaluated_func("gt", greater)?
+            .add_evaluated_func("le", less_equal)?
+            .add_evaluated_func("ge", greater_equal)?
+            .add_func("zero?", zero)?
+            .add_func("positive?", positive)?
+            .add_func("negative?", negative)?
+            .add_func("even?", even)?
+            .add_func("odd?", odd)?
+            .add_evaluated_func("min", min)?
+            .add_evaluated_func("max", max)?
+            .add_func("abs", f32::abs)?
             // Symbol names
-            .add_func("+", add)?
-            .add_func("-", minus)?
-            .add_func("*", times)?
-            .add_func("/", divide)?
-            .add_func("%", modulus)?
+            .add_evaluated_func("+", add)?
+            .add_evaluated_func("-", minus)?
+            .add_evaluated_func("*", times)?
+            .add_evaluated_func("/", divide)?
+            .add_evaluated_func("%", modulus)?
             .add_func("==", equal)?
-            .add_func("!=", notequal)
+            .add_func("!=", notequal)?
+            .add_evaluated_func("<", less)?
+            .add_evaluated_func(">", greater)?
+            .add_evaluated_func("<=", less_equal)?
+            .add_evaluated_func(">=", greater_equal)
     }
 }
 
-fn cons(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let first;
-    let second;
+fn cons(a: Rc<Object>, b: Rc<Object>) -> Object {
+    Object::pair(a, b)
+}
 
-    match &*arg {
-        Object::Pair(a, b) => {
-            first = lisp.eval_object(Rc::clone(a))?;
+// Combine each number given into the first, taking them one at a time as these are called too
+// often to collect them first
+fn fold_numbers(lisp: &mut Lisp, arg: Rc<Object>, fold: fn(f32, f32) -> f32) -> RustFuncResult {
+    let mut args = Args::new(lisp, arg);
+    let mut total = f32::take(&mut args)?;
 
-            match &**b {
-                Object::Pair(a, b) => {
-                    second = lisp.eval_object(Rc::clone(a))?;
+    while let Some(n) = args.next_value()? {
+        let n = f32::from_lisp(&n).map_err(RustFuncError::new_args_error)?;
 
-                    match &**b {
-                        Object::Nil => (),
-                        _ => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-                    }
-                }
-                Object::Nil => {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough))
-                }
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-            }
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
+        total = fold(total, n);
+    }
 
-    Ok(Rc::new(Object::Pair(first, second)))
+    Ok(total.into_lisp())
 }
 
 fn modulus(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut sum;
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            sum = match *lisp.eval_object(Rc::clone(a))? {
-                Object::Number(i) => i,
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            };
-
-            b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match &**cur_object {
-            Object::Pair(a, b) => {
-                sum %= match *lisp.eval_object(Rc::clone(a))? {
-                    Object::Number(i) => i,
-                    _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-                };
-
-                cur_object = b
-            }
-            Object::Nil => break Ok(Rc::new(Object::Number(sum))),
-            _ => break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+    fold_numbers(lisp, arg, |a, b| a % b)
 }
 
 fn divide(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut sum;
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            sum = match *lisp.eval_object(Rc::clone(a))? {
-                Object::Number(i) => i,
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            };
-
-            b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match &**cur_object {
-            Object::Pair(a, b) => {
-                sum /= match *lisp.eval_object(Rc::clone(a))? {
-                    Object::Number(i) => i,
-                    _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-                };
-
-                cur_object = b
-            }
-            Object::Nil => break Ok(Rc::new(Object::Number(sum))),
-            _ => break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+    fold_numbers(lisp, arg, |a, b| a / b)
 }
 
 fn times(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut sum;
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            sum = match *lisp.eval_object(Rc::clone(a))? {
-                Object::Number(i) => i,
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            };
-
-            b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match &**cur_object {
-            Object::Pair(a, b) => {
-                sum *= match *lisp.eval_object(Rc::clone(a))? {
-                    Object::Number(i) => i,
-                    _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-                };
-
-                cur_object = b
-            }
-            Object::Nil => break Ok(Rc::new(Object::Number(sum))),
-            _ => break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+    fold_numbers(lisp, arg, |a, b| a * b)
 }
 
 fn minus(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut sum;
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            sum = match *lisp.eval_object(Rc::clone(a))? {
-                Object::Number(i) => i,
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            };
-
-            b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match &**cur_object {
-            Object::Pair(a, b) => {
-                sum -= match *lisp.eval_object(Rc::clone(a))? {
-                    Object::Number(i) => i,
-                    _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-                };
-
-                cur_object = b
-            }
-            Object::Nil => break Ok(Rc::new(Object::Number(sum))),
-            _ => break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+    fold_numbers(lisp, arg, |a, b| a - b)
 }
 
 fn add(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut sum;
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            sum = match *lisp.eval_object(Rc::clone(a))? {
-                Object::Number(i) => i,
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            };
-
-            b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match &**cur_object {
-            Object::Pair(a, b) => {
-                sum += match *lisp.eval_object(Rc::clone(a))? {
-                    Object::Number(i) => i,
-                    _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-                };
-
-                cur_object = b;
-            }
-            Object::Nil => break Ok(Rc::new(Object::Number(sum))),
-            _ => break Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
+    fold_numbers(lisp, arg, |a, b| a + b)
+}
+
+// Check two objects are the same object
+fn identical(a: Rc<Object>, b: Rc<Object>) -> bool {
+    Object::identical(&a, &b)
+}
+
+// Check two objects are the same object, or the same number or character
+fn equivalent(a: Rc<Object>, b: Rc<Object>) -> bool {
+    a.eqv(&b)
+}
+
+// Compare each number given with the next one
+fn compare(lisp: &mut Lisp, arg: Rc<Object>, compare: fn(f32, f32) -> bool) -> RustFuncResult {
+    let mut args = Args::new(lisp, arg);
+    let mut last = f32::take(&mut args)?;
+    let mut ordered = true;
+
+    while let Some(n) = args.next_value()? {
+        let n = f32::from_lisp(&n).map_err(RustFuncError::new_args_error)?;
+
+        ordered &= compare(last, n);
+        last = n;
     }
+
+    Ok(ordered.into_lisp())
 }
 
-// Set variable
-fn set(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let (symbol, data) = match &*arg {
-        Object::Pair(a, b) => match &**b {
-            Object::Pair(c, d) => {
-                if **d != Object::Nil {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough));
-                }
-
-                (a, c)
-            }
-            Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        },
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    if let Object::Symbol(symbol) = &**symbol {
-        let data = lisp.eval_object(Rc::clone(data))?;
-        lisp.set_var(symbol, data)?;
-    } else {
+fn less(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    compare(lisp, arg, |a, b| a < b)
+}
+
+fn greater(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    compare(lisp, arg, |a, b| a > b)
+}
+
+fn less_equal(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    compare(lisp, arg, |a, b| a <= b)
+}
+
+fn greater_equal(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    compare(lisp, arg, |a, b| a >= b)
+}
+
+fn zero(n: f32) -> bool {
+    n == 0.0
+}
+
+fn positive(n: f32) -> bool {
+    n > 0.0
+}
+
+fn negative(n: f32) -> bool {
+    n < 0.0
+}
+
+// Only whole numbers can be even or odd
+fn parity(n: f32, remainder: f32) -> Result<bool, RustFuncError> {
+    if n % 1.0 != 0.0 {
         return Err(RustFuncError::new_args_error(ArgumentsError::WrongType));
     }
 
+    Ok((n % 2.0).abs() == remainder)
+}
+
+fn even(n: f32) -> Result<bool, RustFuncError> {
+    parity(n, 0.0)
+}
+
+fn odd(n: f32) -> Result<bool, RustFuncError> {
+    parity(n, 1.0)
+}
+
+fn min(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    fold_numbers(lisp, arg, f32::min)
+}
+
+fn max(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    fold_numbers(lisp, arg, f32::max)
+}
+
+// Set variable
+fn set(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (Form(symbol), data): (Form<Symbol>, Rc<Object>) = lisp.parse_args(arg)?;
+
+    lisp.set_var(&symbol, data)?;
+
     Ok(Rc::new(Object::Nil))
 }
 
 // Define global
 fn define(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let (symbol, data) = match &*arg {
-        Object::Pair(a, b) => match &**b {
-            Object::Pair(c, d) => {
-                if **d != Object::Nil {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough));
-                }
-
-                (a, c)
-            }
-            Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        },
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    if let Object::Symbol(symbol) = &**symbol {
-        let data = lisp.eval_object(Rc::clone(data))?;
-        lisp.add_var(true, symbol, data)?;
-    } else {
-        return Err(RustFuncError::new_args_error(ArgumentsError::WrongType));
-    }
+    let (Form(symbol), data): (Form<Symbol>, Rc<Object>) = lisp.parse_args(arg)?;
+
+    lisp.add_var(true, &symbol, data)?;
 
     Ok(Rc::new(Object::Nil))
 }
 
 // Define global function
 fn defunc(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let (symbol, function) = match &*arg {
-        Object::Pair(a, b) => (a, func(lisp, Rc::clone(b))?),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
+    let (Form(symbol), Form(params), Rest(body)): (Form<Symbol>, Form<Vec<Symbol>>, Rest<Form>) =
+        lisp.parse_args(arg)?;
 
-    if let Object::Symbol(symbol) = &**symbol {
-        lisp.add_var(true, symbol, function)?;
-    } else {
-        return Err(RustFuncError::new_args_error(ArgumentsError::WrongType));
-    }
+    let function = new_function(lisp, params, body);
+    lisp.add_var(true, &symbol, function)?;
 
     Ok(Rc::new(Object::Nil))
 }
 
+// Free cycles of objects which are no longer used, giving how many containers were freed, or nil
+// if it couldn't as the heap is shared
+fn collect_garbage(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let () = lisp.parse_args(arg)?;
+
+    Ok(match lisp.collect_garbage() {
+        Some(n) => Rc::new(Object::Number(n as f32)),
+        None => Rc::new(Object::Nil),
+    })
+}
+
 // Evaluate an object and what it returns
 fn eval(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut objects = Vec::new();
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            objects.push(lisp.eval_object(Rc::clone(a))?);
-
-            &**b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match cur_object {
-            Object::Pair(a, b) => {
-                objects.push(lisp.eval_object(Rc::clone(a))?);
-
-                cur_object = &*b
-            }
-            Object::Nil => break,
-            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+    let (first, Rest(mut objects)): (Rc<Object>, Rest<Rc<Object>>) = lisp.parse_args(arg)?;
+
+    objects.insert(0, first);
 
     Ok(lisp.eval_objects(objects)?)
 }
 
 // Evaluates the given object forever
 fn lispwhile(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let cond;
-    let mut objects = Vec::new();
-    let mut cur_object = match &*arg {
-        Object::Pair(a, b) => {
-            cond = a;
-
-            &*b
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    loop {
-        match &**cur_object {
-            Object::Pair(a, b) => {
-                objects.push(Rc::clone(a));
-
-                cur_object = &*b
-            }
-            Object::Nil => break,
-            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+    let (Form(cond), Rest(objects)): (Form, Rest<Form>) = lisp.parse_args(arg)?;
+    let objects: Vec<Rc<Object>> = objects.into_iter().map(|Form(o)| o).collect();
 
     lisp.scope_create();
 
-    while *lisp.eval_object(cond.clone())? != Object::Nil {
-        lisp.eval_objects(objects.clone())?;
-    }
+    let ret = lisp.eval_while(cond, &objects);
 
     lisp.scope_end();
 
+    ret?;
+
     Ok(Rc::new(Object::Nil))
 }
 
 // Evaluates the given object forever conditionally
 fn lispif(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let first;
-    let second;
-    let third;
-
-    match &*arg {
-        Object::Pair(a, b) => {
-            first = a;
-
-            match &**b {
-                Object::Pair(a, b) => {
-                    second = a;
-
-                    match &**b {
-                        Object::Pair(a, b) => {
-                            third = Some(a);
-
-                            match &**b {
-                                Object::Nil => (),
-                                _ => {
-                                    return Err(RustFuncError::new_args_error(
-                                        ArgumentsError::NotEnough,
-                                    ))
-                                }
-                            }
-                        }
-                        Object::Nil => third = None,
-                        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-                    }
-                }
-                Object::Nil => {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough))
-                }
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-            }
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
+    let (Form(first), Form(second), Form(third)): (Form, Form, Form<Option<Rc<Object>>>) =
+        lisp.parse_args(arg)?;
 
     lisp.scope_create();
 
-    let ret = if *lisp.eval_object(first.clone())? != Object::Nil {
-        lisp.eval_object(second.clone())?
-    } else if let Some(third) = third {
-        lisp.eval_object(third.clone())?
-    } else {
-        Rc::new(Object::Nil)
-    };
+    let ret = if_branch(lisp, first, second, third);
 
     lisp.scope_end();
 
-    Ok(ret)
-}
-
-fn equal(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let first;
-    let second;
-
-    match &*arg {
-        Object::Pair(a, b) => {
-            first = a;
-
-            match &**b {
-                Object::Pair(a, b) => {
-                    second = a;
-
-                    match &**b {
-                        Object::Nil => (),
-                        _ => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-                    }
-                }
-                Object::Nil => {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough))
-                }
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-            }
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    if *lisp.eval_object(Rc::clone(first))? == *lisp.eval_object(Rc::clone(second))? {
-        Ok(Rc::new(Object::True))
-    } else {
-        Ok(Rc::new(Object::Nil))
-    }
+    Ok(ret?)
 }
 
-fn notequal(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let first;
-    let second;
-
-    match &*arg {
-        Object::Pair(a, b) => {
-            first = a;
-
-            match &**b {
-                Object::Pair(a, b) => {
-                    second = a;
-
-                    match **b {
-                        Object::Nil => (),
-                        _ => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-                    }
-                }
-                Object::Nil => {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough))
-                }
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-            }
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    if *lisp.eval_object(Rc::clone(first))? != *lisp.eval_object(Rc::clone(second))? {
-        Ok(Rc::new(Object::True))
+fn if_branch(
+    lisp: &mut Lisp,
+    first: Rc<Object>,
+    second: Rc<Object>,
+    third: Option<Rc<Object>>,
+) -> LispResult {
+    if *lisp.eval_object(first)? != Object::Nil {
+        lisp.eval_object(second)
+    } else if let Some(third) = third {
+        lisp.eval_object(third)
     } else {
         Ok(Rc::new(Object::Nil))
     }
 }
 
+fn equal(a: Rc<Object>, b: Rc<Object>) -> bool {
+    *a == *b
+}
+
+fn notequal(a: Rc<Object>, b: Rc<Object>) -> bool {
+    *a != *b
+}
+
 // Returns whatever its given, used for when you don't want to evaluate something
-fn quote(_: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                Err(RustFuncError::new_args_error(ArgumentsError::TooMany))
-            } else {
-                Ok(Rc::clone(a))
-            }
-        }
-        Object::Nil => Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    }
+fn quote(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (Form(object),): (Form,) = lisp.parse_args(arg)?;
+
+    Ok(object)
 }
 
 // For creating functions
-fn func(_: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let mut lisp_list_args;
-    let mut func_body = Vec::new();
-
-    match &*arg {
-        Object::Pair(a, b) => {
-            match **a {
-                Object::Pair(_, _) => lisp_list_args = a,
-                Object::Nil => lisp_list_args = a,
-                _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            }
-
-            let mut cur_object = &*b;
-
-            loop {
-                match &**cur_object {
-                    Object::Pair(a, b) => {
-                        func_body.push(Rc::clone(a));
-
-                        cur_object = &*b
-                    }
-                    Object::Nil => break,
-                    _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-                }
-            }
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    let mut args: Vec<String> = Vec::new();
-
-    loop {
-        match &**lisp_list_args {
-            Object::Pair(a, b) => {
-                if let Object::Symbol(s) = &**a {
-                    args.push(s.to_string());
-                } else {
-                    return Err(RustFuncError::new_args_error(ArgumentsError::WrongType));
-                }
-
-                lisp_list_args = b
-            }
-            Object::Nil => break,
-            _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-        }
-    }
+fn func(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (Form(params), Rest(body)): (Form<Vec<Symbol>>, Rest<Form>) = lisp.parse_args(arg)?;
+
+    Ok(new_function(lisp, params, body))
+}
+
+fn new_function(lisp: &Lisp, params: Vec<Symbol>, body: Vec<Form>) -> Rc<Object> {
+    let params = params.iter().map(|p| lisp.own_symbol(p)).collect();
+    let body = body.into_iter().map(|Form(o)| o).collect();
 
-    Ok(Rc::new(Object::LispFunc(args, func_body)))
+    Rc::new(Object::LispFunc(LispFunction::new(params, body)))
 }
 
 // Get first item in a list
-fn car(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
-
-            match &*lisp.eval_object(Rc::clone(a))? {
-                Object::Pair(a, _) => Ok(Rc::clone(a)),
-                _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            }
-        }
-        Object::Nil => Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+fn car(list: Rc<Object>) -> RustFuncResult {
+    match &*list {
+        Object::Pair(a, _) => Ok(a.get()),
+        _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
     }
 }
 
 // Get next item in a list
-fn cdr(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
-
-            match &*lisp.eval_object(Rc::clone(a))? {
-                Object::Pair(_, b) => Ok(Rc::clone(b)),
-                _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-            }
-        }
-        Object::Nil => Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
+fn cdr(list: Rc<Object>) -> RustFuncResult {
+    match &*list {
+        Object::Pair(_, b) => Ok(b.get()),
+        _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
     }
 }
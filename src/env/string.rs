@@ -0,0 +1,318 @@
+// Functions for working with strings, which are lists of characters
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::Length;
+use crate::{
+    args::{FromLisp, Rest},
+    errors::*,
+    lisp::LispBuilder,
+    object::Object,
+    rc::Rc,
+};
+
+impl LispBuilder {
+    pub fn add_env_string(self) -> Result<Self, LispError> {
+        self.add_func("string-length", string_length)?
+            .add_func("string-append", string_append)?
+            .add_func("substring", substring)?
+            .add_func("string-split", string_split)?
+            .add_func("string-join", string_join)?
+            .add_func("string-index", string_index)?
+            .add_func("string-contains?", string_contains)?
+            .add_func("string-upcase", string_upcase)?
+            .add_func("string-downcase", string_downcase)?
+            .add_func("string-trim", string_trim)?
+            .add_func("string->list", string_to_list)?
+            .add_func("list->string", list_to_string)?
+            .add_func("string=?", string_equal)?
+            .add_func("string<?", string_less)?
+            .add_func("number->string", number_to_string)?
+            .add_func("string->number", string_to_number)?
+            .add_func("format", format)?
+            // Characters
+            .add_func("char->integer", char_to_integer)?
+            .add_func("integer->char", integer_to_char)?
+            .add_func("char-alphabetic?", char::is_alphabetic)?
+            .add_func("char-numeric?", char::is_numeric)?
+            .add_func("char-whitespace?", char::is_whitespace)?
+            .add_func("char-upcase", char_upcase)?
+            .add_func("char-downcase", char_downcase)
+    }
+}
+
+// Strings searched for may also be given as a single character
+struct Needle(String);
+
+impl FromLisp for Needle {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match **object {
+            Object::Character(c) => Ok(Needle(c.to_string())),
+            _ => String::from_lisp(object).map(Needle),
+        }
+    }
+}
+
+// Get the number of characters in a string
+fn string_length(string: String) -> usize {
+    string.chars().count()
+}
+
+// Join all given strings together
+fn string_append(Rest(strings): Rest<String>) -> String {
+    strings.concat()
+}
+
+// Get the characters from start up to end, or the end of the string
+fn substring(string: String, start: usize, end: Option<usize>) -> Result<String, RustFuncError> {
+    let chars: Vec<char> = string.chars().collect();
+    let end = end.unwrap_or(chars.len());
+
+    if start > end || end > chars.len() {
+        return Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange));
+    }
+
+    Ok(chars[start..end].iter().collect())
+}
+
+// Split a string by a separator, or by whitespace if none is given
+fn string_split(string: String, separator: Option<Needle>) -> Result<Vec<String>, RustFuncError> {
+    let parts = match separator {
+        Some(Needle(s)) if s.is_empty() => {
+            return Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange))
+        }
+        Some(Needle(s)) => string.split(s.as_str()).map(String::from).collect(),
+        None => string.split_whitespace().map(String::from).collect(),
+    };
+
+    Ok(parts)
+}
+
+// Join a list of strings, with an optional separator between them
+fn string_join(strings: Vec<String>, separator: Option<Needle>) -> String {
+    match separator {
+        Some(Needle(s)) => strings.join(&s),
+        None => strings.concat(),
+    }
+}
+
+// Find the position of a string or character within a string, or nil if absent
+fn string_index(string: String, Needle(needle): Needle) -> Option<usize> {
+    string.find(&needle).map(|i| string[..i].chars().count())
+}
+
+fn string_contains(string: String, Needle(needle): Needle) -> bool {
+    string.contains(&needle)
+}
+
+fn string_upcase(string: String) -> String {
+    string.to_uppercase()
+}
+
+fn string_downcase(string: String) -> String {
+    string.to_lowercase()
+}
+
+// Remove whitespace from both ends of a string
+fn string_trim(string: String) -> String {
+    string.trim().to_string()
+}
+
+// Strings are already lists, so this gives a fresh copy of the characters
+fn string_to_list(string: String) -> String {
+    string
+}
+
+// Check a list only holds characters, copying it into a string
+fn list_to_string(chars: Vec<char>) -> String {
+    chars.into_iter().collect()
+}
+
+// Compare each string given with the next one
+fn compare_strings(first: String, rest: Vec<String>, compare: fn(&str, &str) -> bool) -> bool {
+    let mut last = first;
+    let mut ordered = true;
+
+    for s in rest {
+        ordered &= compare(&last, &s);
+        last = s;
+    }
+
+    ordered
+}
+
+fn string_equal(first: String, Rest(rest): Rest<String>) -> bool {
+    compare_strings(first, rest, |a, b| a == b)
+}
+
+fn string_less(first: String, Rest(rest): Rest<String>) -> bool {
+    compare_strings(first, rest, |a, b| a < b)
+}
+
+// A base to write numbers in
+struct Radix(u32);
+
+impl FromLisp for Radix {
+    fn from_lisp(object: &Rc<Object>) -> Result<Self, ArgumentsError> {
+        match usize::from_lisp(object)? {
+            r @ 2..=36 => Ok(Radix(r as u32)),
+            _ => Err(ArgumentsError::OutOfRange),
+        }
+    }
+}
+
+// Write a number in any radix, giving the fractional part to a number of digits
+fn number_in_radix(number: f32, radix: u32, precision: Option<usize>) -> String {
+    if radix == 10 || !number.is_finite() {
+        return match precision {
+            Some(p) => format!("{:.*}", p, number),
+            None => format!("{}", number),
+        };
+    }
+
+    let mut string = String::new();
+    let mut whole = number.abs().trunc();
+    let mut fraction = number.abs().fract();
+
+    if number < 0.0 {
+        string.push('-');
+    }
+
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((whole % radix as f32) as u32, radix).unwrap());
+        whole = (whole / radix as f32).trunc();
+
+        if whole == 0.0 {
+            break;
+        }
+    }
+    string.extend(digits.iter().rev());
+
+    // Without a precision, stop once the fraction runs out
+    let max_digits = precision.unwrap_or(16);
+
+    if max_digits > 0 && (precision.is_some() || fraction != 0.0) {
+        string.push('.');
+
+        for _ in 0..max_digits {
+            fraction *= radix as f32;
+            string.push(char::from_digit(fraction.trunc() as u32, radix).unwrap());
+            fraction = fraction.fract();
+
+            if precision.is_none() && fraction == 0.0 {
+                break;
+            }
+        }
+    }
+
+    string
+}
+
+// Convert a number to a string, with an optional radix and precision
+fn number_to_string(number: f32, radix: Option<Radix>, precision: Option<Length>) -> String {
+    let Radix(radix) = radix.unwrap_or(Radix(10));
+
+    number_in_radix(number, radix, precision.map(|Length(p)| p))
+}
+
+// Read a number from a string, giving nil if it isn't one
+fn string_to_number(string: String, radix: Option<Radix>) -> Option<f32> {
+    let string = string.trim();
+
+    match radix {
+        None | Some(Radix(10)) => string.parse::<f32>().ok(),
+        Some(Radix(r)) => i64::from_str_radix(string, r).ok().map(|n| n as f32),
+    }
+}
+
+// Fill in a template string, where:
+// ~a displays an object, ~s writes it as code, ~d writes a number,
+// ~% is a newline and ~~ is a tilde
+pub(crate) fn format_objects(template: &str, args: &[Rc<Object>]) -> Result<String, RustFuncError> {
+    let mut string = String::new();
+    let mut args = args.iter();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '~' {
+            string.push(c);
+            continue;
+        }
+
+        let directive = chars.next();
+
+        if let Some('%') = directive {
+            string.push('\n');
+            continue;
+        } else if let Some('~') = directive {
+            string.push('~');
+            continue;
+        }
+
+        let arg = match args.next() {
+            Some(a) => a,
+            None => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
+        };
+
+        match directive {
+            Some('a') | Some('A') => string.push_str(&arg.display().to_string()),
+            Some('s') | Some('S') => string.push_str(&arg.to_string()),
+            Some('d') | Some('D') => {
+                let n = f32::from_lisp(arg).map_err(RustFuncError::new_args_error)?;
+
+                string.push_str(&n.to_string())
+            }
+            _ => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+        }
+    }
+
+    if args.next().is_some() {
+        return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
+    }
+
+    Ok(string)
+}
+
+// Create a string from a template and objects to fill it in with
+fn format(template: String, Rest(args): Rest<Rc<Object>>) -> Result<String, RustFuncError> {
+    format_objects(&template, &args)
+}
+
+// Get the unicode value of a character
+fn char_to_integer(c: char) -> f32 {
+    c as u32 as f32
+}
+
+// Get the character with a unicode value
+fn integer_to_char(code: usize) -> Result<char, RustFuncError> {
+    match u32::try_from(code).ok().and_then(char::from_u32) {
+        Some(c) => Ok(c),
+        None => Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange)),
+    }
+}
+
+// Change the case of a character, leaving it alone if it doesn't map to a single character
+fn char_convert<I>(c: char, convert: fn(char) -> I) -> char
+where
+    I: Iterator<Item = char>,
+{
+    let mut converted = convert(c);
+
+    match (converted.next(), converted.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+fn char_upcase(c: char) -> char {
+    char_convert(c, char::to_uppercase)
+}
+
+fn char_downcase(c: char) -> char {
+    char_convert(c, char::to_lowercase)
+}
@@ -1,15 +1,14 @@
-use alloc::{
-    rc::Rc,
-    string::{String, ToString},
-    vec::Vec,
-};
+use alloc::string::{String, ToString};
 
 use std::{fs::File, io::Read};
 
+use super::string::format_objects;
 use crate::{
+    args::{FromLisp, Rest},
     errors::*,
     lisp::{Lisp, LispBuilder},
     object::Object,
+    rc::Rc,
 };
 
 impl LispBuilder {
@@ -19,84 +18,55 @@ impl LispBuilder {
             .add_func("read", read)?
             .add_func("exit", exit)?
             .add_func("print", print)?
+            .add_func("display", display)?
             .add_func("print-raw", print_raw)
     }
 }
 
 // Exit lisp interpreter, number may be provided for exit code
-fn exit(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let exit_code = match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
-
-            if let Object::Number(n) = *lisp.eval_object(Rc::clone(a))? {
-                n
-            } else {
-                return Err(RustFuncError::new_args_error(ArgumentsError::WrongType));
-            }
-        }
-        _ => 0.0,
-    };
-
-    std::process::exit(exit_code as i32);
+fn exit(code: Option<f32>) {
+    std::process::exit(code.unwrap_or(0.0) as i32);
 }
 
-// Display an object
-fn print(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let a = match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
-            lisp.eval_object(Rc::clone(a))?
-        }
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
+// Display an object, or a formatted string if given a template and objects to fill it in with
+fn print(first: Rc<Object>, Rest(rest): Rest<Rc<Object>>) -> Result<(), RustFuncError> {
+    if rest.is_empty() {
+        println!("{}", first);
+    } else {
+        let template = String::from_lisp(&first).map_err(RustFuncError::new_args_error)?;
 
-    println!("{}", a);
+        println!("{}", format_objects(&template, &rest)?);
+    }
 
-    Ok(Rc::new(Object::Nil))
+    Ok(())
 }
 
-// Display an objects raw internals
-fn print_raw(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let a = match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
-            lisp.eval_object(Rc::clone(a))?
-        }
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    println!("{:?}", a);
+// Display an object for people to read, without quotes around strings
+fn display(object: Rc<Object>) {
+    println!("{}", object.display());
+}
 
-    Ok(Rc::new(Object::Nil))
+// Display an objects raw internals
+fn print_raw(object: Rc<Object>) {
+    println!("{:?}", object);
 }
 
 // Reads a line into objects
 fn read(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
     use std::io::{stdin, stdout, Write};
 
-    let promptobject = match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
+    let (prompt,): (Option<Rc<Object>>,) = lisp.parse_args(arg)?;
+    let promptobject = match prompt {
+        Some(o) => o,
+        None => {
+            let symbol = lisp.intern("PROMPT");
 
-            Rc::clone(a)
+            lisp.eval_symbol(&symbol)
+                .unwrap_or_else(|_| Rc::new(Object::Nil))
         }
-        Object::Nil => match lisp.eval_object(Rc::new(Object::Symbol("PROMPT".to_string()))) {
-            Ok(o) => o,
-            Err(_) => Rc::new(Object::Nil),
-        },
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
     };
 
-    let prompt = match &*lisp.eval_object(promptobject)? {
+    let prompt = match &*promptobject {
         Object::Character(c) => format!("{} ", c),
         Object::Nil => "> ".to_string(),
         v => {
@@ -116,8 +86,7 @@ fn read(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
     stdout.flush().unwrap();
 
     stdin.read_line(&mut input).unwrap();
-    let objects = Object::eval(&input)?; // Evaluate tokens into objects
-    let objects: Vec<Rc<Object>> = objects.into_iter().map(Rc::new).collect(); // Store objects on the heap
+    let objects = lisp.read(&input)?; // Evaluate tokens into objects
 
     // Read cannot return multiple objects, even if multiple objects were evaluated
     if !objects.is_empty() {
@@ -129,22 +98,7 @@ fn read(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
 
 // Include another file, causing execution to switch to that file
 fn include(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
-    let file = match &*arg {
-        Object::Pair(a, b) => {
-            if **b != Object::Nil {
-                return Err(RustFuncError::new_args_error(ArgumentsError::TooMany));
-            }
-
-            lisp.eval_object(Rc::clone(a))?
-        }
-        Object::Nil => return Err(RustFuncError::new_args_error(ArgumentsError::NotEnough)),
-        _ => return Err(RustFuncError::new_args_error(ArgumentsError::DottedPair)),
-    };
-
-    let file = match file.pair_list_to_string() {
-        Ok(s) => s,
-        Err(_) => return Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
-    };
+    let (file,): (String,) = lisp.parse_args(arg)?;
 
     let mut file = match File::open(&file) {
         Err(why) => panic!("couldn't open: {}", why),
@@ -156,10 +110,9 @@ fn include(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
         panic!("couldn't read: {}", why);
     }
 
-    let objects = Object::eval(&data)?; // Evaluate tokens into objects
-    let objects: Vec<Rc<Object>> = objects.into_iter().map(Rc::new).collect(); // Store objects on the heap
+    let objects = lisp.read(&data)?; // Evaluate tokens into objects
 
-    let mut scope = Lisp::new(lisp.globals.clone());
+    let mut scope = lisp.share_globals();
 
     let mut ret = Rc::new(Object::Nil);
     for o in objects {
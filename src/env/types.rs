@@ -0,0 +1,114 @@
+// Functions for finding out what type of value an object holds
+
+use alloc::string::ToString;
+
+use crate::{
+    errors::*,
+    lisp::{Lisp, LispBuilder},
+    object::Object,
+    rc::Rc,
+    symbol::Symbol,
+};
+
+impl LispBuilder {
+    pub fn add_env_types(self) -> Result<Self, LispError> {
+        self.add_evaluated_func("type-of", type_of)?
+            .add_func("number?", number)?
+            .add_func("symbol?", symbol)?
+            .add_func("keyword?", keyword)?
+            .add_func("string?", string)?
+            .add_func("char?", char)?
+            .add_func("function?", function)?
+            .add_func("procedure?", function)?
+            .add_func("vector?", vector)?
+            .add_func("hash-table?", hash_table)?
+            .add_func("nil?", nil)?
+            .add_evaluated_func("keyword->symbol", keyword_to_symbol)?
+            .add_func("symbol->keyword", symbol_to_keyword)
+    }
+}
+
+// Get a symbol naming the type of an object, rust types using the name they were given
+fn type_of(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (object,): (Rc<Object>,) = lisp.parse_args(arg)?;
+
+    let name = match &*object {
+        Object::Nil => "nil",
+        Object::True => "true",
+        Object::Pair(_, _) => "pair",
+        Object::Symbol(_) => "symbol",
+        Object::Keyword(_) => "keyword",
+        Object::Number(_) => "number",
+        Object::Character(_) => "character",
+        Object::Quoted(_) => "quoted",
+        Object::Vector(_) => "vector",
+        Object::HashTable(_) => "hash-table",
+        Object::LispFunc(_) => "function",
+        Object::RustFunc(_) | Object::RustClosure(_) => "builtin",
+        Object::RustType(t) => {
+            let name = match lisp.type_names.borrow().get(&t.type_id()) {
+                Some(n) => lisp.intern(n),
+                None => lisp.intern(t.host_name().unwrap_or("rust-type")),
+            };
+
+            return Ok(Rc::new(Object::Symbol(name)));
+        }
+    };
+
+    Ok(Rc::new(Object::Symbol(lisp.intern(name))))
+}
+
+fn number(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Number(_))
+}
+
+fn symbol(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Symbol(_))
+}
+
+fn keyword(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Keyword(_))
+}
+
+// Any list of characters, including the empty list as it is also the empty string
+fn string(object: Rc<Object>) -> bool {
+    object.pair_list_to_string().is_ok()
+}
+
+fn char(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Character(_))
+}
+
+// Anything that can be called, whether written in lisp or rust
+fn function(object: Rc<Object>) -> bool {
+    matches!(
+        *object,
+        Object::LispFunc(_) | Object::RustFunc(_) | Object::RustClosure(_)
+    )
+}
+
+fn vector(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Vector(_))
+}
+
+fn hash_table(object: Rc<Object>) -> bool {
+    matches!(*object, Object::HashTable(_))
+}
+
+fn nil(object: Rc<Object>) -> bool {
+    matches!(*object, Object::Nil)
+}
+
+// The symbol with the same name as a keyword, without the colon
+fn keyword_to_symbol(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (keyword,): (Rc<Object>,) = lisp.parse_args(arg)?;
+
+    match &*keyword {
+        Object::Keyword(s) => Ok(Rc::new(Object::Symbol(lisp.intern(s)))),
+        _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+    }
+}
+
+fn symbol_to_keyword(symbol: Symbol) -> Object {
+    Object::Keyword(symbol.name().to_string())
+}
@@ -0,0 +1,112 @@
+// Vectors, which can be indexed and changed in place
+
+use alloc::{vec, vec::Vec};
+
+use super::Length;
+use crate::{
+    args::Rest,
+    errors::*,
+    lisp::{Lisp, LispBuilder},
+    object::Object,
+    rc::{Rc, RefCell},
+};
+
+impl LispBuilder {
+    pub fn add_env_vectors(self) -> Result<Self, LispError> {
+        self.add_func("vector", vector)?
+            .add_func("make-vector", make_vector)?
+            .add_func("vector-ref", vector_ref)?
+            .add_evaluated_func("vector-set!", vector_set)?
+            .add_func("vector-length", vector_length)?
+            .add_func("vector->list", vector_to_list)?
+            .add_func("list->vector", list_to_vector)?
+            .add_evaluated_func("vector-push!", vector_push)?
+            .add_evaluated_func("vector-map", vector_map)
+    }
+}
+
+fn new_vector(objects: Vec<Rc<Object>>) -> Rc<Object> {
+    Rc::new(Object::Vector(RefCell::new(objects)))
+}
+
+fn expect_vector(object: &Object) -> Result<&RefCell<Vec<Rc<Object>>>, RustFuncError> {
+    match object {
+        Object::Vector(v) => Ok(v),
+        _ => Err(RustFuncError::new_args_error(ArgumentsError::WrongType)),
+    }
+}
+
+// Create a vector from the arguments
+fn vector(Rest(objects): Rest<Rc<Object>>) -> Rc<Object> {
+    new_vector(objects)
+}
+
+// Create a vector of a length, filled with an object or nil
+fn make_vector(Length(len): Length, fill: Option<Rc<Object>>) -> Rc<Object> {
+    let fill = fill.unwrap_or_else(|| Rc::new(Object::Nil));
+
+    new_vector(vec![fill; len])
+}
+
+fn vector_ref(vector: Rc<Object>, index: usize) -> RustFuncResult {
+    match expect_vector(&vector)?.borrow().get(index) {
+        Some(o) => Ok(Rc::clone(o)),
+        None => Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange)),
+    }
+}
+
+// Replace the object at an index of a vector
+fn vector_set(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (vector, index, object): (Rc<Object>, usize, Rc<Object>) = lisp.parse_args(arg)?;
+
+    match expect_vector(&vector)?.borrow_mut().get_mut(index) {
+        Some(o) => *o = object,
+        None => return Err(RustFuncError::new_args_error(ArgumentsError::OutOfRange)),
+    }
+
+    lisp.track(&vector);
+
+    Ok(Rc::new(Object::Nil))
+}
+
+fn vector_length(vector: Rc<Object>) -> Result<usize, RustFuncError> {
+    Ok(expect_vector(&vector)?.borrow().len())
+}
+
+fn vector_to_list(vector: Rc<Object>) -> Result<Vec<Rc<Object>>, RustFuncError> {
+    Ok(expect_vector(&vector)?.borrow().clone())
+}
+
+fn list_to_vector(list: Vec<Rc<Object>>) -> Rc<Object> {
+    new_vector(list)
+}
+
+// Add objects to the end of a vector
+fn vector_push(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (vector, first, Rest(rest)): (Rc<Object>, Rc<Object>, Rest<Rc<Object>>) =
+        lisp.parse_args(arg)?;
+
+    let mut objects = expect_vector(&vector)?.borrow_mut();
+
+    objects.push(first);
+    objects.extend(rest);
+
+    drop(objects);
+    lisp.track(&vector);
+
+    Ok(Rc::new(Object::Nil))
+}
+
+// Create a vector from the results of calling a function on each object of a vector
+fn vector_map(lisp: &mut Lisp, arg: Rc<Object>) -> RustFuncResult {
+    let (function, vector): (Rc<Object>, Rc<Object>) = lisp.parse_args(arg)?;
+    // Copied so the function can change the vector while it is mapped over
+    let objects = expect_vector(&vector)?.borrow().clone();
+
+    let results = objects
+        .into_iter()
+        .map(|o| lisp.call_function(&function, vec![o]))
+        .collect::<Result<Vec<_>, LispError>>()?;
+
+    Ok(new_vector(results))
+}
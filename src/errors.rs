@@ -1,4 +1,4 @@
-use alloc::{boxed::Box, rc::Rc, string::String};
+use alloc::{boxed::Box, string::String};
 
 #[cfg(feature = "std")]
 use std::{error::Error, fmt};
@@ -6,7 +6,7 @@ use std::{error::Error, fmt};
 #[cfg(not(feature = "std"))]
 use core::{error::Error, fmt};
 
-use crate::object::Object;
+use crate::{object::Object, rc::Rc};
 
 pub type LispResult = Result<Rc<Object>, LispError>;
 pub type RustFuncResult = Result<Rc<Object>, RustFuncError>;
@@ -135,6 +135,13 @@ pub enum ArgumentsError {
     NotEnough,
     WrongType,
     DottedPair,
+    OutOfRange,
+    InvalidPattern(String),
+    UnknownKeyword(String),
+    // The name of the rust type which was expected
+    ExpectedRustType(&'static str),
+    // A hash table key which could never be found again
+    InvalidKey,
 }
 
 impl fmt::Display for ArgumentsError {
@@ -144,6 +151,11 @@ impl fmt::Display for ArgumentsError {
             Self::NotEnough => write!(f, "Not enough arguments"),
             Self::WrongType => write!(f, "Arguments of wrong type"),
             Self::DottedPair => write!(f, "Dotted-pair arguments not accepted"),
+            Self::OutOfRange => write!(f, "Argument out of range"),
+            Self::InvalidPattern(e) => write!(f, "Invalid pattern: {}", e),
+            Self::UnknownKeyword(k) => write!(f, "Unknown keyword: :{}", k),
+            Self::ExpectedRustType(t) => write!(f, "Expected a rust value of type {}", t),
+            Self::InvalidKey => write!(f, "Invalid hash table key"),
         }
     }
 }
@@ -0,0 +1,221 @@
+// Collecting cycles of objects, which reference counting alone never frees
+//
+// Only pairs, vectors and hash tables can be changed after they are made, and a cycle can only be
+// made by changing one, so every one that is changed is remembered as a candidate. Collecting
+// finds every object reachable from the candidates, and counts how many of the references to each
+// come from within that graph. Those with references from outside are alive, as is everything they
+// reach, and the rest are only kept alive by each other, so are emptied to break their cycles
+
+use alloc::vec::Vec;
+
+use crate::{
+    lisp::Lisp,
+    object::Object,
+    rc::{Rc, Weak},
+    symbol::SymbolMap,
+};
+
+// Fewest candidates to collect automatically at
+const MIN_THRESHOLD: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeapStats {
+    // Containers which have been changed and are still alive, so may be part of a cycle
+    pub candidates: usize,
+    // Number of candidates which causes a collection
+    pub threshold: usize,
+    pub collections: usize,
+    // Containers freed by every collection
+    pub collected: usize,
+    // Containers freed by the last collection
+    pub last_collected: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct Heap {
+    // Keyed by address, so changing a container many times only remembers it once
+    candidates: SymbolMap<Weak<Object>>,
+    stats: HeapStats,
+}
+
+impl Heap {
+    pub(crate) fn new() -> Self {
+        Self {
+            candidates: SymbolMap::default(),
+            stats: HeapStats {
+                candidates: 0,
+                threshold: MIN_THRESHOLD,
+                collections: 0,
+                collected: 0,
+                last_collected: 0,
+            },
+        }
+    }
+
+    // Remember a changed container, giving if there are now enough to collect
+    fn track(&mut self, object: &Rc<Object>) -> bool {
+        if !can_change(object) {
+            return false;
+        }
+
+        self.candidates
+            .insert(Rc::as_ptr(object) as usize, Rc::downgrade(object));
+
+        self.candidates.len() >= self.stats.threshold
+    }
+
+    fn collect(&mut self) -> usize {
+        // Every object reachable from the candidates, with the number of references to it from
+        // the others, and whether it has been found to be alive
+        let mut graph: SymbolMap<(Rc<Object>, usize, bool)> = SymbolMap::default();
+        let mut unvisited: Vec<Rc<Object>> = Vec::new();
+
+        for c in self.candidates.values().filter_map(Weak::upgrade) {
+            graph.entry(Rc::as_ptr(&c) as usize).or_insert_with(|| {
+                unvisited.push(Rc::clone(&c));
+                (c, 0, false)
+            });
+        }
+
+        while let Some(o) = unvisited.pop() {
+            children(&o, |child| {
+                graph
+                    .entry(Rc::as_ptr(&child) as usize)
+                    .or_insert_with(|| {
+                        unvisited.push(Rc::clone(&child));
+                        (child, 0, false)
+                    })
+                    .1 += 1;
+            });
+        }
+
+        // The graph holds one reference to each object, so any more are from outside it
+        let mut alive: Vec<usize> = graph
+            .iter()
+            .filter(|(_, (o, refs, _))| Rc::strong_count(o) > refs + 1)
+            .map(|(k, _)| *k)
+            .collect();
+
+        while let Some(k) = alive.pop() {
+            let object = match graph.get_mut(&k) {
+                Some((_, _, true)) | None => continue,
+                Some((o, _, found)) => {
+                    *found = true;
+                    Rc::clone(o)
+                }
+            };
+
+            children(&object, |child| alive.push(Rc::as_ptr(&child) as usize));
+        }
+
+        let garbage: Vec<Rc<Object>> = graph
+            .into_values()
+            .filter(|(_, _, alive)| !alive)
+            .map(|(o, _, _)| o)
+            .collect();
+
+        let nil = Rc::new(Object::Nil);
+
+        for o in &garbage {
+            match &**o {
+                Object::Pair(a, b) => {
+                    a.set(Rc::clone(&nil));
+                    b.set(Rc::clone(&nil));
+                }
+                Object::Vector(v) => v.borrow_mut().clear(),
+                Object::HashTable(t) => t.borrow_mut().clear(),
+                _ => (),
+            }
+        }
+
+        let collected = garbage.len();
+        drop(garbage);
+
+        self.candidates.retain(|_, c| c.strong_count() > 0);
+
+        // Candidates which are still alive are checked again each time, so wait for more of them
+        self.stats.candidates = self.candidates.len();
+        self.stats.threshold = MIN_THRESHOLD.max(self.candidates.len() * 2);
+        self.stats.collections += 1;
+        self.stats.collected += collected;
+        self.stats.last_collected = collected;
+
+        collected
+    }
+}
+
+fn can_change(object: &Object) -> bool {
+    matches!(
+        object,
+        Object::Pair(_, _) | Object::Vector(_) | Object::HashTable(_)
+    )
+}
+
+// Call a function with every object directly referenced by an object which can be part of a cycle
+fn children(object: &Object, mut f: impl FnMut(Rc<Object>)) {
+    let mut child = |o: Rc<Object>| {
+        if can_change(&o) || matches!(*o, Object::Quoted(_) | Object::LispFunc(_)) {
+            f(o)
+        }
+    };
+
+    match object {
+        Object::Pair(a, b) => {
+            child(a.get());
+            child(b.get());
+        }
+        Object::Quoted(o) => child(Rc::clone(o)),
+        Object::Vector(v) => v.borrow().iter().for_each(|o| child(Rc::clone(o))),
+        Object::HashTable(t) => {
+            for (k, v) in t.borrow().iter() {
+                child(Rc::clone(k));
+                child(Rc::clone(v));
+            }
+        }
+        Object::LispFunc(func) => func.body().iter().for_each(|o| child(Rc::clone(o))),
+        _ => (),
+    }
+}
+
+impl Lisp {
+    // Remember a container which has been changed, as it may now be part of a cycle. Builtins do
+    // this themselves, but rust code changing containers should too
+    pub fn track(&self, object: &Rc<Object>) {
+        let collect = self.heap.borrow_mut().track(object);
+
+        if collect {
+            self.collect_garbage();
+        }
+    }
+
+    // Whether no other interpreter shares this heap, which with sync could be on another thread
+    #[cfg(feature = "sync")]
+    fn owns_heap(&self) -> bool {
+        Rc::strong_count(&self.heap) == 1
+    }
+
+    #[cfg(not(feature = "sync"))]
+    fn owns_heap(&self) -> bool {
+        true
+    }
+
+    // Free every cycle which is no longer referenced, giving the number of containers freed, or
+    // nothing while another interpreter shares the heap.
+    //
+    // The references counted must not change while it runs. With sync, no other thread may use
+    // objects from this interpreter while it collects, as a container changed part way through
+    // could be emptied while it is still in use. Interpreters sharing the heap could be on other
+    // threads, but objects given to other threads directly aren't known about
+    pub fn collect_garbage(&self) -> Option<usize> {
+        self.owns_heap().then(|| self.heap.borrow_mut().collect())
+    }
+
+    pub fn heap_stats(&self) -> HeapStats {
+        let heap = self.heap.borrow();
+
+        HeapStats {
+            candidates: heap.candidates.len(),
+            ..heap.stats
+        }
+    }
+}
@@ -15,13 +15,134 @@ lithia = "*"
 
 # Example: simple REPL
 
+```rust,no_run
+use lithia::lisp::LispBuilder;
+
+let code = "(while t (print (eval (read))))".to_string();
+
+let mut lisp = LispBuilder::new()
+    .add_default_envs()
+    .unwrap()
+    .build();
+
+lisp.eval(&code).unwrap();
+```
+
+# Example: rust closure
+
+Closures can capture state, which plain rust functions can't
+
 ```rust
-let code = "(while t (print (eval (read))))".to_string()
+use lithia::{lisp::LispBuilder, object::Object, rc::{Rc, RefCell}};
+
+let count = Rc::new(RefCell::new(0.0));
+let counter = Rc::clone(&count);
+
+let mut lisp = LispBuilder::new()
+    .add_default_envs()
+    .unwrap()
+    .add_closure("count", move |_, _| {
+        *counter.borrow_mut() += 1.0;
 
-let lisp = LispBuilder::new()
-    .add_default_envs();
+        Ok(Rc::new(Object::Number(*counter.borrow())))
+    })
+    .unwrap()
+    .build();
 
-lisp.eval(&code);
+lisp.eval("(count) (count)").unwrap();
+
+assert_eq!(*count.borrow(), 2.0);
+```
+
+# Example: typed rust function
+
+Functions taking rust values have their arguments evaluated and converted for them, with the
+wrong number or type of arguments being an error
+
+```rust
+use lithia::{lisp::LispBuilder, object::Object};
+
+let mut lisp = LispBuilder::new()
+    .add_func("hypot", |a: f32, b: f32| a.hypot(b))
+    .unwrap()
+    .build();
+
+assert_eq!(*lisp.eval("(hypot 3 4)").unwrap(), Object::Number(5.0));
+assert!(lisp.eval("(hypot 3 4 5)").is_err());
+```
+
+# Example: calling lisp
+
+Lisp functions can be called from rust with rust values, and what they return converted back
+
+```rust
+use lithia::lisp::LispBuilder;
+
+let mut lisp = LispBuilder::new()
+    .add_default_envs()
+    .unwrap()
+    .build();
+
+lisp.eval("(defunc mean (xs) (/ (apply + xs) (length xs)))").unwrap();
+
+let mean: f32 = lisp.call("mean", (vec![1.0, 2.0, 6.0],)).unwrap();
+
+assert_eq!(mean, 3.0);
+assert!(lisp.call::<String>("mean", (vec![1.0],)).is_err());
+```
+
+# Example: variables
+
+Variables can be given to a script before it is run, and read back afterwards
+
+```rust
+use lithia::lisp::LispBuilder;
+
+let mut lisp = LispBuilder::new()
+    .add_default_envs()
+    .unwrap()
+    .build();
+
+lisp.define("width", 3.0).unwrap();
+lisp.eval("(set area (* width width))").unwrap();
+
+assert_eq!(lisp.get::<f32>("area").unwrap(), 9.0);
+assert!(lisp.remove("area").is_some());
+assert!(!lisp.contains("area"));
+```
+
+# Example: rust types
+
+Rust values can be held by objects and taken back out, and implementing `HostType` lets them be
+named, printed and compared
+
+```rust
+use lithia::{
+    lisp::LispBuilder,
+    object::{HostType, Object},
+    rc::Rc,
+};
+
+struct Celsius(f32);
+
+impl HostType for Celsius {
+    fn type_name(&self) -> &str {
+        "celsius"
+    }
+}
+
+let mut lisp = LispBuilder::new()
+    .add_default_envs()
+    .unwrap()
+    .add_var("room", Rc::new(Object::new_host_type(Celsius(21.0))))
+    .unwrap()
+    .build();
+
+let room = lisp.eval("room").unwrap();
+
+assert_eq!(room.downcast_ref::<Celsius>().unwrap().0, 21.0);
+assert!(room.downcast_ref::<f32>().is_err());
+assert_eq!(lisp.eval("(type-of room)").unwrap().to_string(), "celsius");
 ```
 */
 
@@ -31,7 +152,15 @@ lisp.eval(&code);
 
 extern crate alloc;
 
+#[cfg(feature = "macros")]
+pub use lithia_macros::{function, LispType};
+
+pub mod args;
+pub mod bytecode;
 pub mod env;
 pub mod errors;
+pub mod gc;
 pub mod lisp;
 pub mod object;
+pub mod rc;
+pub mod symbol;
@@ -1,11 +1,10 @@
 use alloc::{
-    rc::Rc,
     string::{String, ToString},
     vec,
     vec::Vec,
 };
 
-use core::cell::RefCell;
+use core::any::TypeId;
 
 #[cfg(feature = "std")]
 use std::collections::HashMap;
@@ -13,7 +12,22 @@ use std::collections::HashMap;
 #[cfg(not(feature = "std"))]
 use hashbrown::HashMap;
 
-use crate::{errors::*, object::Object};
+use crate::{
+    args::{FromLisp, IntoArgs, IntoBuiltin, IntoLisp},
+    bytecode::{Code, SpecialForm, SpecialForms},
+    env::regex::RegexCache,
+    errors::*,
+    gc::Heap,
+    object::{Closure, LispFunction, Object},
+    rc::{Rc, RefCell},
+    symbol::{Symbol, SymbolMap, SymbolTable},
+};
+
+// Something which adds itself to an interpreter as it is built, generated for functions by
+// `#[lithia::function]` and for structs by `#[derive(lithia::LispType)]`
+pub trait Register {
+    fn register(builder: LispBuilder) -> Result<LispBuilder, LispError>;
+}
 
 #[derive(Clone)]
 pub struct LispBuilder {
@@ -22,21 +36,95 @@ pub struct LispBuilder {
 
 impl LispBuilder {
     pub fn new() -> Self {
-        Self {
-            lisp: Lisp::new(Rc::new(RefCell::new(HashMap::new()))),
-        }
+        Self { lisp: Lisp::new() }
     }
 
     pub fn add_var(mut self, name: &str, object: Rc<Object>) -> Result<Self, LispError> {
-        self.lisp.add_var(true, name, object).map(|_| self)
+        let symbol = self.lisp.intern(name);
+
+        self.lisp.add_var(true, &symbol, object).map(|_| self)
+    }
+
+    // Either a function given its arguments unevaluated, or one with typed parameters such as
+    // `|a: f32, b: f32| a.powf(b)`, which has its arguments evaluated and checked for it
+    pub fn add_func<Marker, F: IntoBuiltin<Marker>>(
+        mut self,
+        name: &str,
+        func: F,
+    ) -> Result<Self, LispError> {
+        let symbol = self.lisp.intern(name);
+        let func = Rc::new(Object::RustClosure(func.into_builtin()));
+
+        if F::EVALUATED {
+            self.lisp.add_evaluated(&func);
+        }
+
+        self.lisp.add_var(true, &symbol, func).map(|_| self)
+    }
+
+    // Rust closures, which can capture state such as handles to the program embedding lisp
+    pub fn add_closure<F: Closure + 'static>(
+        mut self,
+        name: &str,
+        func: F,
+    ) -> Result<Self, LispError> {
+        self.lisp
+            .add_closure(true, name, Rc::new(func))
+            .map(|_| self)
     }
 
-    pub fn add_func(
+    // Special forms which the bytecode compiler knows how to compile directly
+    pub(crate) fn add_compiled_form(
         mut self,
         name: &str,
         func: fn(&mut Lisp, Rc<Object>) -> RustFuncResult,
+        form: SpecialForm,
     ) -> Result<Self, LispError> {
-        self.lisp.add_func(true, name, func).map(|_| self)
+        let symbol = self.lisp.intern(name);
+        let func = Rc::new(Object::RustFunc(func));
+
+        self.lisp
+            .special_forms
+            .borrow_mut()
+            .insert(Rc::as_ptr(&func) as usize, (Rc::clone(&func), form));
+
+        self.lisp.add_var(true, &symbol, func).map(|_| self)
+    }
+
+    // Rust functions which start by evaluating all of their arguments, such as by parsing them
+    // all as values, so compiled code can evaluate the arguments for them
+    pub(crate) fn add_evaluated_func(
+        mut self,
+        name: &str,
+        func: fn(&mut Lisp, Rc<Object>) -> RustFuncResult,
+    ) -> Result<Self, LispError> {
+        let symbol = self.lisp.intern(name);
+        let func = Rc::new(Object::RustFunc(func));
+
+        self.lisp.add_evaluated(&func);
+        self.lisp.add_var(true, &symbol, func).map(|_| self)
+    }
+
+    // Compile objects into bytecode before running them, instead of walking them as they are
+    pub fn use_bytecode(mut self, bytecode: bool) -> Self {
+        self.lisp.bytecode = bytecode;
+
+        self
+    }
+
+    // Name a rust type, so type-of can report it for objects holding that type
+    pub fn add_type_name<T: 'static>(self, name: &str) -> Self {
+        self.lisp
+            .type_names
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), name.to_string());
+
+        self
+    }
+
+    // Add everything a rust type or function exposes to lisp, usually derived with the macros
+    pub fn register<T: Register>(self) -> Result<Self, LispError> {
+        T::register(self)
     }
 
     pub fn build(self) -> Lisp {
@@ -44,97 +132,257 @@ impl LispBuilder {
     }
 }
 
+impl Default for LispBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone)]
 pub struct Lisp {
-    scope: Vec<HashMap<String, Rc<Object>>>,
-    pub globals: Rc<RefCell<HashMap<String, Rc<Object>>>>,
+    // Local variables of every scope and function call, newest last
+    locals: Vec<(usize, Rc<Object>)>,
+    // Where each scope starts in locals
+    scopes: Vec<usize>,
+    // Where the locals of the function being run start, as it can't see those of its callers
+    frame: usize,
+    // Values being worked on, by bytecode and while evaluating arguments
+    pub(crate) stack: Vec<Rc<Object>>,
+    pub(crate) globals: Rc<RefCell<SymbolMap<Rc<Object>>>>,
+    pub(crate) symbols: Rc<RefCell<SymbolTable>>,
+    // Which table the symbols are from, kept here as it is checked on every lookup
+    table: usize,
+    pub(crate) regex_cache: Rc<RefCell<RegexCache>>,
+    pub(crate) type_names: Rc<RefCell<HashMap<TypeId, String>>>,
+    pub(crate) special_forms: Rc<RefCell<SpecialForms>>,
+    // Builtins which evaluate each of their arguments in order, keyed by their address like
+    // special forms
+    pub(crate) evaluated: Rc<RefCell<SymbolMap<Rc<Object>>>>,
+    pub(crate) heap: Rc<RefCell<Heap>>,
+    bytecode: bool,
 }
 
 impl Lisp {
-    pub(crate) fn new(globals: Rc<RefCell<HashMap<String, Rc<Object>>>>) -> Self {
+    pub(crate) fn new() -> Self {
+        let symbols = SymbolTable::new();
+
+        Self {
+            locals: Vec::new(),
+            scopes: vec![0],
+            frame: 0,
+            stack: Vec::new(),
+            globals: Rc::new(RefCell::new(SymbolMap::default())),
+            table: symbols.table(),
+            symbols: Rc::new(RefCell::new(symbols)),
+            regex_cache: Rc::new(RefCell::new(RegexCache::default())),
+            type_names: Rc::new(RefCell::new(HashMap::new())),
+            special_forms: Rc::new(RefCell::new(SymbolMap::default())),
+            evaluated: Rc::new(RefCell::new(SymbolMap::default())),
+            heap: Rc::new(RefCell::new(Heap::new())),
+            bytecode: false,
+        }
+    }
+
+    // New interpreter sharing the globals of this one, but none of its local variables
+    #[cfg(feature = "std")]
+    pub(crate) fn share_globals(&self) -> Self {
         Self {
-            scope: vec![HashMap::new()],
-            globals,
+            locals: Vec::new(),
+            scopes: vec![0],
+            frame: 0,
+            stack: Vec::new(),
+            globals: Rc::clone(&self.globals),
+            symbols: Rc::clone(&self.symbols),
+            table: self.table,
+            regex_cache: Rc::clone(&self.regex_cache),
+            type_names: Rc::clone(&self.type_names),
+            special_forms: Rc::clone(&self.special_forms),
+            evaluated: Rc::clone(&self.evaluated),
+            heap: Rc::clone(&self.heap),
+            bytecode: self.bytecode,
         }
     }
 
+    // Switch between running bytecode and walking objects, which can be easier to debug
+    pub fn set_bytecode(&mut self, bytecode: bool) {
+        self.bytecode = bytecode;
+    }
+
+    // The special form a global currently refers to, if it does
+    pub(crate) fn special_form(&self, symbol: &Symbol) -> Option<(Rc<Object>, SpecialForm)> {
+        let function = Rc::clone(self.globals.borrow().get(&self.symbol_id(symbol))?);
+
+        self.special_forms
+            .borrow()
+            .get(&(Rc::as_ptr(&function) as usize))
+            .map(|(_, form)| (function, *form))
+    }
+
+    // The builtin a global currently refers to, if it evaluates all of its arguments
+    pub(crate) fn evaluated_builtin(&self, symbol: &Symbol) -> Option<Rc<Object>> {
+        let function = Rc::clone(self.globals.borrow().get(&self.symbol_id(symbol))?);
+        let address = Rc::as_ptr(&function) as usize;
+
+        self.evaluated
+            .borrow()
+            .contains_key(&address)
+            .then_some(function)
+    }
+
+    fn add_evaluated(&mut self, function: &Rc<Object>) {
+        self.evaluated
+            .borrow_mut()
+            .insert(Rc::as_ptr(function) as usize, Rc::clone(function));
+    }
+
     // New scope
     pub(crate) fn scope_create(&mut self) {
-        self.scope.push(HashMap::new());
+        self.scopes.push(self.locals.len());
     }
 
     // End scope
     pub(crate) fn scope_end(&mut self) {
-        self.scope.pop();
+        if let Some(start) = self.scopes.pop() {
+            self.locals.truncate(start);
+        }
+    }
+
+    pub(crate) fn scope_depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    // End every scope created since there were a number of them
+    pub(crate) fn scope_end_to(&mut self, depth: usize) {
+        if let Some(&start) = self.scopes.get(depth) {
+            self.locals.truncate(start);
+            self.scopes.truncate(depth);
+        }
+    }
+
+    // Get the symbol with a name, which is shared by every interpreter sharing these globals
+    pub fn intern(&self, name: &str) -> Symbol {
+        self.symbols.borrow_mut().intern(name)
+    }
+
+    // The number of a symbol in this interpreter's table. Symbols given by another table, such
+    // as those read by the deprecated `Object::eval`, are found again by name, as their numbers
+    // mean nothing here
+    pub(crate) fn symbol_id(&self, symbol: &Symbol) -> usize {
+        match symbol.table() == self.table {
+            true => symbol.id(),
+            false => self.intern(symbol.name()).id(),
+        }
+    }
+
+    // The same symbol from this interpreter's table
+    pub(crate) fn own_symbol(&self, symbol: &Symbol) -> Symbol {
+        match symbol.table() == self.table {
+            true => symbol.clone(),
+            false => self.intern(symbol.name()),
+        }
     }
 
     pub(crate) fn add_var(
         &mut self,
         global: bool,
-        name: &str,
+        symbol: &Symbol,
         object: Rc<Object>,
     ) -> Result<(), LispError> {
+        let id = self.symbol_id(symbol);
+
         if global {
             let mut globals = self.globals.borrow_mut();
 
-            match globals.get(name) {
+            match globals.get(&id) {
                 Some(_) => {
                     return Err(LispError::new(
                         LispErrorKind::Eval,
-                        EvalError::GlobalExists(name.to_string()),
+                        EvalError::GlobalExists(symbol.name().to_string()),
                     ))
                 }
-                None => globals.insert(name.to_string(), object),
+                None => {
+                    globals.insert(id, object);
+                }
             }
         } else {
-            let len = self.scope.len();
-            self.scope[len - 1].insert(name.to_string(), object)
+            let start = self.scopes.last().copied().unwrap_or(self.frame);
+
+            match self.locals[start..].iter_mut().find(|(i, _)| *i == id) {
+                Some((_, o)) => *o = object,
+                None => self.locals.push((id, object)),
+            }
         };
 
         Ok(())
     }
 
-    pub(crate) fn add_func(
+    pub(crate) fn add_closure(
         &mut self,
         global: bool,
         name: &str,
-        func: fn(&mut Lisp, Rc<Object>) -> RustFuncResult,
+        func: Rc<dyn Closure>,
     ) -> Result<(), LispError> {
-        self.add_var(global, name, Rc::new(Object::RustFunc(func)))
+        let symbol = self.intern(name);
+
+        self.add_var(global, &symbol, Rc::new(Object::RustClosure(func)))
     }
 
-    fn eval_symbol(&self, symbol: &str) -> LispResult {
-        if !symbol.is_empty() {
-            for s in self.scope.iter().rev() {
-                if let Some(o) = s.get(symbol) {
-                    return Ok(o.clone());
-                }
+    pub(crate) fn eval_symbol(&self, symbol: &Symbol) -> LispResult {
+        let id = self.symbol_id(symbol);
+
+        for (i, o) in self.locals[self.frame..].iter().rev() {
+            if *i == id {
+                return Ok(o.clone());
             }
+        }
 
-            // Check globals
-            match self.globals.borrow_mut().get(symbol) {
-                Some(o) => Ok(o.clone()),
-                None => Err(LispError::new(
-                    LispErrorKind::Eval,
-                    EvalError::UnknownSymbol(symbol.to_string()),
-                )),
+        // Check globals
+        match self.globals.borrow().get(&id) {
+            Some(o) => Ok(o.clone()),
+            None => Err(LispError::new(
+                LispErrorKind::Eval,
+                EvalError::UnknownSymbol(symbol.name().to_string()),
+            )),
+        }
+    }
+
+    // A parameter of the function being run, by where it was bound in its frame. Rust functions
+    // may have removed locals since, so the name is checked and looked up if it doesn't match
+    pub(crate) fn eval_local(&self, offset: usize, symbol: &Symbol) -> LispResult {
+        match self.locals.get(self.frame + offset) {
+            Some((id, o)) if *id == symbol.id() => Ok(Rc::clone(o)),
+            _ => self.eval_symbol(symbol),
+        }
+    }
+
+    pub(crate) fn set_local(
+        &mut self,
+        offset: usize,
+        symbol: &Symbol,
+        data: Rc<Object>,
+    ) -> Result<(), LispError> {
+        match self.locals.get_mut(self.frame + offset) {
+            Some((id, o)) if *id == symbol.id() => {
+                *o = data;
+                Ok(())
             }
-        } else {
-            Ok(Rc::new(Object::Nil))
+            _ => self.set_var(symbol, data),
         }
     }
 
-    pub(crate) fn set_var(&mut self, symbol: &str, data: Rc<Object>) -> Result<(), LispError> {
+    pub(crate) fn set_var(&mut self, symbol: &Symbol, data: Rc<Object>) -> Result<(), LispError> {
+        let id = self.symbol_id(symbol);
+
         // Check for variable, going up scope if it can't find it
-        for v in self.scope.iter_mut().rev() {
-            if let Some(s) = v.get_mut(symbol) {
-                *s = data;
+        for (i, o) in self.locals[self.frame..].iter_mut().rev() {
+            if *i == id {
+                *o = data;
                 return Ok(());
             }
         }
 
         // Check for variable in globals
-        if let Some(s) = self.globals.borrow_mut().get_mut(symbol) {
+        if let Some(s) = self.globals.borrow_mut().get_mut(&id) {
             *s = data;
             return Ok(());
         }
@@ -145,55 +393,112 @@ impl Lisp {
         Ok(())
     }
 
+    // The value of a variable, as lisp would see it where the interpreter is
+    pub fn get<T: FromLisp>(&self, name: &str) -> Result<T, LispError> {
+        from_lisp(&self.eval_name(name)?)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.eval_name(name).is_ok()
+    }
+
+    // Look up a variable by name, without interning names which have never been seen, as they
+    // can't refer to anything
+    fn eval_name(&self, name: &str) -> LispResult {
+        let symbol = self.symbols.borrow().get(name);
+
+        match symbol {
+            Some(s) => self.eval_symbol(&s),
+            None => Err(LispError::new(
+                LispErrorKind::Eval,
+                EvalError::UnknownSymbol(name.to_string()),
+            )),
+        }
+    }
+
+    // Change a variable, or create a local one if there isn't one, the same as `set`
+    pub fn set(&mut self, name: &str, value: impl IntoLisp) -> Result<(), LispError> {
+        self.set_var(&self.intern(name), value.into_lisp())
+    }
+
+    // Create a global variable, the same as `def`
+    pub fn define(&mut self, name: &str, value: impl IntoLisp) -> Result<(), LispError> {
+        self.add_var(true, &self.intern(name), value.into_lisp())
+    }
+
+    // Remove the variable a name refers to, the innermost local one, or else the global one,
+    // giving its value
+    pub fn remove(&mut self, name: &str) -> Option<Rc<Object>> {
+        let id = self.symbols.borrow().get(name)?.id();
+
+        if let Some(i) = self.locals[self.frame..]
+            .iter()
+            .rposition(|(i, _)| *i == id)
+        {
+            let i = self.frame + i;
+
+            // Scopes after it now start one earlier
+            for start in self.scopes.iter_mut().filter(|s| **s > i) {
+                *start -= 1;
+            }
+
+            return Some(self.locals.remove(i).1);
+        }
+
+        self.globals.borrow_mut().remove(&id)
+    }
+
+    // Every global variable, in no particular order
+    pub fn global_vars(&self) -> Vec<(Symbol, Rc<Object>)> {
+        let symbols = self.symbols.borrow();
+
+        self.globals
+            .borrow()
+            .iter()
+            .filter_map(|(id, o)| Some((symbols.symbol(*id)?, Rc::clone(o))))
+            .collect()
+    }
+
+    // Every local variable the function being run can see, outermost first, without those
+    // hidden by a later one of the same name
+    pub fn scope_vars(&self) -> Vec<(Symbol, Rc<Object>)> {
+        let symbols = self.symbols.borrow();
+        let locals = &self.locals[self.frame..];
+
+        let mut vars: Vec<_> = locals
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(i, (id, _))| !locals[i + 1..].iter().any(|(later, _)| later == id))
+            .filter_map(|(_, (id, o))| Some((symbols.symbol(*id)?, Rc::clone(o))))
+            .collect();
+
+        vars.reverse();
+        vars
+    }
+
     pub(crate) fn eval_object(&mut self, object: Rc<Object>) -> LispResult {
         match &*object {
             Object::Pair(ref f, ref a) => {
                 // Execute expression
-                match &*self.eval_object(f.clone())? {
-                    Object::RustFunc(f) => match f(self, Rc::clone(a)) {
-                        Ok(x) => Ok(x),
-                        Err(e) => Err(LispError::new(LispErrorKind::RustFunc, e)),
-                    },
-                    Object::LispFunc(p, b) => {
-                        let mut args = Vec::new();
-                        let objects = b.iter().map(Rc::clone).collect(); // Store objects on the heap
-
-                        // Create args
-                        let mut cur_object = a;
-
-                        loop {
-                            match &**cur_object {
-                                Object::Pair(a, b) => {
-                                    args.push(self.eval_object(a.clone())?);
-
-                                    cur_object = b
-                                }
-                                Object::Nil => break,
-                                _ => {
-                                    return Err(LispError::new(
-                                        LispErrorKind::RustFunc,
-                                        RustFuncError::new_args_error(ArgumentsError::DottedPair),
-                                    ))
-                                }
-                            }
-                        }
+                let function = self.eval_object(f.get())?;
 
-                        let mut scope = Lisp::new(self.globals.clone());
-
-                        for (i, p) in p.iter().enumerate() {
-                            match args.get(i) {
-                                Some(a) => scope.add_var(false, p, a.clone())?,
-                                None => {
-                                    return Err(LispError::new(
-                                        LispErrorKind::RustFunc,
-                                        RustFuncError::new_args_error(ArgumentsError::NotEnough),
-                                    ))
-                                }
-                            };
+                match &*function {
+                    Object::RustFunc(_) | Object::RustClosure(_) => {
+                        self.call_builtin(&function, a.get())
+                    }
+                    Object::LispFunc(func) => {
+                        // Evaluate the arguments onto the stack, where they are bound from
+                        let base = self.stack.len();
+                        let ret = self.eval_args(a.get());
+
+                        match ret {
+                            Ok(()) => self.call_lisp_func(func, base),
+                            Err(e) => {
+                                self.stack.truncate(base);
+                                Err(e)
+                            }
                         }
-
-                        // Call function
-                        scope.eval_objects(objects)
                     }
                     Object::Character(_) => Ok(Rc::clone(&object)),
                     _ => Err(LispError::new(
@@ -208,7 +513,143 @@ impl Lisp {
         }
     }
 
+    // Evaluate a list of arguments, pushing their values onto the stack
+    fn eval_args(&mut self, mut args: Rc<Object>) -> Result<(), LispError> {
+        loop {
+            match &*Rc::clone(&args) {
+                Object::Pair(a, b) => {
+                    let value = self.eval_object(a.get())?;
+                    self.stack.push(value);
+
+                    args = b.get();
+                }
+                Object::Nil => return Ok(()),
+                _ => {
+                    return Err(LispError::new(
+                        LispErrorKind::RustFunc,
+                        RustFuncError::new_args_error(ArgumentsError::DottedPair),
+                    ))
+                }
+            }
+        }
+    }
+
+    // Run a lisp function with the already evaluated arguments on the stack from base, which are
+    // taken off it
+    pub(crate) fn call_lisp_func(&mut self, func: &LispFunction, base: usize) -> LispResult {
+        let frame = self.frame;
+        let scopes = self.scopes.len();
+        let locals = self.locals.len();
+
+        self.frame = locals;
+        self.scopes.push(locals);
+
+        let ret = match func.bind(&mut self.locals, self.stack.drain(base..)) {
+            // Call function, compiling it the first time it is called
+            Ok(()) if self.bytecode => self.run(func.code(self)),
+            Ok(()) => {
+                let mut ret = Ok(Rc::new(Object::Nil));
+
+                for o in func.body().iter() {
+                    ret = self.eval_object(Rc::clone(o));
+
+                    if ret.is_err() {
+                        break;
+                    }
+                }
+
+                ret
+            }
+            Err(e) => Err(LispError::new(
+                LispErrorKind::RustFunc,
+                RustFuncError::new_args_error(e),
+            )),
+        };
+
+        // Leave the scopes of the function even if it failed part way through
+        self.stack.truncate(base);
+        self.locals.truncate(locals);
+        self.scopes.truncate(scopes);
+        self.frame = frame;
+
+        ret
+    }
+
+    // Call a rust function or closure, which is given its arguments unevaluated
+    pub(crate) fn call_builtin(&mut self, function: &Rc<Object>, args: Rc<Object>) -> LispResult {
+        let ret = match &**function {
+            Object::RustFunc(f) => f(self, args),
+            Object::RustClosure(f) => f(self, args),
+            _ => {
+                return Err(LispError::new(
+                    LispErrorKind::Eval,
+                    EvalError::NonFunction(Rc::clone(function)),
+                ))
+            }
+        };
+
+        ret.map_err(|e| LispError::new(LispErrorKind::RustFunc, e))
+    }
+
+    // Call a function object with already evaluated arguments, which works for both lisp and rust functions
+    pub fn call_function(&mut self, function: &Rc<Object>, args: Vec<Rc<Object>>) -> LispResult {
+        match &**function {
+            Object::RustFunc(_) | Object::RustClosure(_) => {
+                // Rust functions evaluate their own arguments, so quote those which don't evaluate
+                // to themselves to keep their values
+                let args = args
+                    .into_iter()
+                    .map(|a| match *a {
+                        Object::Pair(..) | Object::Symbol(_) | Object::Quoted(_) => {
+                            Rc::new(Object::Quoted(a))
+                        }
+                        _ => a,
+                    })
+                    .collect();
+
+                self.call_builtin(function, Rc::new(Object::array_to_pair_list(args)))
+            }
+            Object::LispFunc(func) => {
+                let base = self.stack.len();
+                self.stack.extend(args);
+
+                self.call_lisp_func(func, base)
+            }
+            _ => Err(LispError::new(
+                LispErrorKind::Eval,
+                EvalError::NonFunction(Rc::clone(function)),
+            )),
+        }
+    }
+
+    // Call the function a variable refers to with rust values, converting what it returns, so
+    // lisp can give rust callbacks
+    pub fn call<R: FromLisp>(&mut self, name: &str, args: impl IntoArgs) -> Result<R, LispError> {
+        let function = self.eval_name(name)?;
+
+        self.call_value(&function, args)
+    }
+
+    // Call a function object with rust values, converting what it returns
+    pub fn call_value<R: FromLisp>(
+        &mut self,
+        function: &Rc<Object>,
+        args: impl IntoArgs,
+    ) -> Result<R, LispError> {
+        let ret = self.call_function(function, args.into_args())?;
+
+        from_lisp(&ret)
+    }
+
+    // Objects given to be evaluated are compiled each time, while the bodies of functions are
+    // compiled once and kept with them
     pub fn eval_objects(&mut self, objects: Vec<Rc<Object>>) -> LispResult {
+        if self.bytecode {
+            let code = Code::compile(self, &objects);
+
+            return self.run(&code);
+        }
+
         let mut ret = Rc::new(Object::Nil);
 
         for o in objects {
@@ -218,10 +659,47 @@ impl Lisp {
         Ok(ret)
     }
 
+    // Evaluate a body for as long as a condition holds, compiling both once rather than on
+    // every loop
+    pub(crate) fn eval_while(
+        &mut self,
+        cond: Rc<Object>,
+        body: &[Rc<Object>],
+    ) -> Result<(), LispError> {
+        if self.bytecode {
+            let cond = Code::compile(self, &[cond]);
+            let body = Code::compile(self, body);
+
+            while *self.run(&cond)? != Object::Nil {
+                self.run(&body)?;
+            }
+
+            return Ok(());
+        }
+
+        while *self.eval_object(Rc::clone(&cond))? != Object::Nil {
+            for o in body {
+                self.eval_object(Rc::clone(o))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Read objects from a string without evaluating them
+    pub fn read(&self, input: &str) -> Result<Vec<Rc<Object>>, LispError> {
+        Object::read(input, &mut self.symbols.borrow_mut())
+    }
+
     pub fn eval(&mut self, input: &str) -> LispResult {
-        let objects = Object::eval(input)?; // Evaluate tokens into objects
-        let objects = objects.into_iter().map(Rc::new).collect(); // Store objects on the heap
+        let objects = self.read(input)?; // Evaluate tokens into objects
 
         self.eval_objects(objects)
     }
 }
+
+// Convert an object given back to rust, a value of the wrong type being an argument error
+fn from_lisp<T: FromLisp>(object: &Rc<Object>) -> Result<T, LispError> {
+    T::from_lisp(object)
+        .map_err(|e| LispError::new(LispErrorKind::RustFunc, RustFuncError::new_args_error(e)))
+}
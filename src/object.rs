@@ -2,56 +2,378 @@ use regex::Regex;
 
 use alloc::{
     boxed::Box,
-    rc::Rc,
+    format,
     string::{String, ToString},
+    vec,
     vec::Vec,
 };
-use core::any::Any;
+use core::{
+    any::{Any, TypeId},
+    hash::{BuildHasherDefault, Hash, Hasher},
+};
+
+#[cfg(feature = "std")]
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
+
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
+
+// Keys are hashed the same way every time, so their hashes can be kept
+#[cfg(not(feature = "std"))]
+#[allow(deprecated)]
+use core::hash::SipHasher as DefaultHasher;
 
-use crate::{errors::*, lisp::Lisp};
+use crate::{
+    bytecode::Code,
+    errors::*,
+    lisp::Lisp,
+    rc::{AnyValue, OnceCell, Rc, RefCell, Shareable},
+    symbol::{IdHasher, Symbol, SymbolTable},
+};
 
 #[derive(Debug)]
 pub enum Object {
     Nil,
     True,
-    Pair(Rc<Object>, Rc<Object>),
-    Symbol(String),
+    Pair(Slot, Slot),
+    Symbol(Symbol),
+    Keyword(String),
     Number(f32),
     Character(char),
     Quoted(Rc<Object>),
-    LispFunc(Vec<String>, Vec<Rc<Object>>),
+    Vector(RefCell<Vec<Rc<Object>>>),
+    HashTable(RefCell<HashTable>),
+    LispFunc(LispFunction),
     RustFunc(fn(&mut Lisp, Rc<Object>) -> RustFuncResult),
-    RustType(Box<dyn Any>),
+    RustClosure(Rc<dyn Closure>),
+    RustType(RustValue),
+}
+
+// A rust closure which can be called like a RustFunc, but can also capture state
+pub trait Closure: Fn(&mut Lisp, Rc<Object>) -> RustFuncResult + Shareable {}
+
+impl<F: Fn(&mut Lisp, Rc<Object>) -> RustFuncResult + Shareable> Closure for F {}
+
+impl fmt::Debug for dyn Closure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Closure({:p})", self as *const Self as *const ())
+    }
+}
+
+// A rust value held by an object. Any type which is safe to share can be held, but those
+// implementing HostType can also choose how they are named, printed and compared
+pub struct RustValue(Box<dyn Held>);
+
+// Optional behaviour for rust values held by objects, which are otherwise named after their rust
+// type, printed as just that name and only equal to themselves
+pub trait HostType: Any + Shareable {
+    // The name type-of gives, unless another was given with LispBuilder::add_type_name
+    fn type_name(&self) -> &str {
+        core::any::type_name::<Self>()
+    }
+
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#<{}>", self.type_name())
+    }
+
+    // Used by equal? and hash tables
+    fn equals(&self, _other: &Self) -> bool {
+        false
+    }
+
+    // Used by hash tables, so has to be the same for values which are equal. Every value of the
+    // type is hashed the same unless this is given, which works but puts them all in one bucket
+    fn host_hash(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<Self>().hash(&mut state)
+    }
+}
+
+// What RustValue needs from what it holds, whether or not it implements HostType
+trait Held: Shareable {
+    fn as_any(&self) -> &AnyValue;
+    fn host_name(&self) -> Option<&str>;
+    fn rust_name(&self) -> &'static str;
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+    fn equals(&self, other: &AnyValue) -> bool;
+    fn hash(&self, state: &mut dyn Hasher);
+}
+
+struct Plain<T>(T);
+
+impl<T: Any + Shareable> Held for Plain<T> {
+    fn as_any(&self) -> &AnyValue {
+        &self.0
+    }
+
+    fn host_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn rust_name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
+
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#<{}>", self.rust_name())
+    }
+
+    fn equals(&self, _other: &AnyValue) -> bool {
+        false
+    }
+
+    // Only equal to itself, so where it is can be hashed
+    fn hash(&self, mut state: &mut dyn Hasher) {
+        (self as *const Self).hash(&mut state)
+    }
+}
+
+struct Host<T>(T);
+
+impl<T: HostType> Held for Host<T> {
+    fn as_any(&self) -> &AnyValue {
+        &self.0
+    }
+
+    fn host_name(&self) -> Option<&str> {
+        Some(self.0.type_name())
+    }
+
+    fn rust_name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
+
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.display(f)
+    }
+
+    fn equals(&self, other: &AnyValue) -> bool {
+        other.downcast_ref().is_some_and(|o| self.0.equals(o))
+    }
+
+    fn hash(&self, state: &mut dyn Hasher) {
+        self.0.host_hash(state)
+    }
+}
+
+impl RustValue {
+    pub fn new<T: Any + Shareable>(value: T) -> Self {
+        Self(Box::new(Plain(value)))
+    }
+
+    pub fn new_host<T: HostType>(value: T) -> Self {
+        Self(Box::new(Host(value)))
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.as_any().is::<T>()
+    }
+
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.as_any().downcast_ref()
+    }
+
+    // The type of the value held, rather than of the RustValue itself
+    pub fn type_id(&self) -> TypeId {
+        self.0.as_any().type_id()
+    }
+
+    // The name given by HostType, if the value's type implements it
+    pub fn host_name(&self) -> Option<&str> {
+        self.0.host_name()
+    }
+
+    pub fn rust_name(&self) -> &'static str {
+        self.0.rust_name()
+    }
+
+    pub(crate) fn hash(&self, state: &mut dyn Hasher) {
+        self.0.hash(state)
+    }
+}
+
+impl fmt::Display for RustValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.display(f)
+    }
+}
+
+impl fmt::Debug for RustValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RustValue({})", self.rust_name())
+    }
+}
+
+impl PartialEq for RustValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.equals(other.0.as_any())
+    }
+}
+
+// One half of a pair, which can be changed in place
+#[derive(Debug)]
+pub struct Slot(RefCell<Rc<Object>>);
+
+impl Slot {
+    pub fn new(object: Rc<Object>) -> Self {
+        Self(RefCell::new(object))
+    }
+
+    // The borrow ends before returning, so the slot can't be left borrowed while it is set
+    pub fn get(&self) -> Rc<Object> {
+        Rc::clone(&self.0.borrow())
+    }
+
+    pub fn set(&self, object: Rc<Object>) {
+        *self.0.borrow_mut() = object;
+    }
+}
+
+// A function written in lisp, with its parameters resolved to the slots they are bound to when
+// it is created, so calling it only has to push its arguments
+#[derive(Debug)]
+pub struct LispFunction {
+    params: Vec<Symbol>,
+    // Parameters from this index on are given by keyword
+    keys: Option<usize>,
+    // Shared with every copy of the function, as it never changes
+    body: Rc<[Rc<Object>]>,
+    // Compiled the first time it is run as bytecode
+    code: OnceCell<Code>,
+}
+
+impl LispFunction {
+    pub fn new(params: Vec<Symbol>, body: Vec<Rc<Object>>) -> Self {
+        let keys = params.iter().position(|p| p.name() == "&key");
+        let params = params.into_iter().filter(|p| p.name() != "&key").collect();
+
+        Self {
+            params,
+            keys,
+            body: body.into(),
+            code: OnceCell::new(),
+        }
+    }
+
+    pub fn params(&self) -> &[Symbol] {
+        &self.params
+    }
+
+    pub fn body(&self) -> &Rc<[Rc<Object>]> {
+        &self.body
+    }
+
+    pub(crate) fn code(&self, lisp: &Lisp) -> &Code {
+        self.code
+            .get_or_init(|| Code::compile_function(lisp, &self.params, &self.body))
+    }
+
+    // Push the arguments onto the end of locals, one slot for each parameter in order
+    pub(crate) fn bind(
+        &self,
+        locals: &mut Vec<(usize, Rc<Object>)>,
+        mut args: impl Iterator<Item = Rc<Object>>,
+    ) -> Result<(), ArgumentsError> {
+        let start = locals.len();
+        let keys = self.keys.unwrap_or(self.params.len());
+
+        for p in &self.params[..keys] {
+            match args.next() {
+                Some(a) => locals.push((p.id(), a)),
+                None => return Err(ArgumentsError::NotEnough),
+            }
+        }
+
+        if self.keys.is_none() {
+            return Ok(());
+        }
+
+        // Parameters after &key are given by keyword, in any order, and are nil if not given
+        for k in &self.params[keys..] {
+            locals.push((k.id(), Rc::new(Object::Nil)));
+        }
+
+        while let Some(k) = args.next() {
+            let slot = match &*k {
+                Object::Keyword(k) => {
+                    match self.params[keys..].iter().position(|s| s.name() == k) {
+                        Some(i) => start + keys + i,
+                        None => return Err(ArgumentsError::UnknownKeyword(k.clone())),
+                    }
+                }
+                _ => return Err(ArgumentsError::WrongType),
+            };
+
+            match args.next() {
+                Some(a) => locals[slot].1 = a,
+                None => return Err(ArgumentsError::NotEnough),
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Object {
-    fn parse_atom(string: &str) -> Result<Self, LispError> {
+    pub fn pair(first: Rc<Object>, second: Rc<Object>) -> Self {
+        Self::Pair(Slot::new(first), Slot::new(second))
+    }
+
+    // Values which don't implement HostType are only equal to themselves, even if the values they
+    // hold are equal, so are only found in hash tables by the same object
+    pub fn new_rust_type<T: Any + Shareable>(value: T) -> Self {
+        Self::RustType(RustValue::new(value))
+    }
+
+    pub fn new_host_type<T: HostType>(value: T) -> Self {
+        Self::RustType(RustValue::new_host(value))
+    }
+
+    // The rust value held by this object, as long as it is of the type expected
+    pub fn downcast_ref<T: Any>(&self) -> Result<&T, ArgumentsError> {
+        match self {
+            Self::RustType(v) => v.downcast_ref(),
+            _ => None,
+        }
+        .ok_or(ArgumentsError::ExpectedRustType(core::any::type_name::<T>()))
+    }
+
+    fn parse_atom(string: &str, symbols: &mut SymbolTable) -> Result<Self, LispError> {
         if let Ok(i) = str::parse::<f32>(string) {
             Ok(Self::Number(i))
-        } else if string.len() == 2 && string.starts_with('\\') {
-            Ok(Self::Character(string.chars().nth(1).unwrap()))
-        } else if string.starts_with('"') && string.ends_with('"') {
-            let mut string = string.to_string();
-            string.pop();
-
-            let string = string
-                .replace("\\\\", "\\")
-                .replace("\\\"", "\"")
-                .replace("\\t", "\t")
-                .replace("\\r", "\r")
-                .replace("\\n", "\n")
-                .replace("\\0", "\0");
-
-            let objects = string
-                .chars()
-                .skip(1)
-                .map(Self::Character)
-                .map(Rc::new)
-                .collect();
+        } else if let Some(c) = string.strip_prefix('\\').and_then(Self::parse_character) {
+            Ok(Self::Character(c))
+        } else if string.len() > 1 && string.starts_with('"') && string.ends_with('"') {
+            let mut objects = Vec::new();
+            let mut chars = string[1..string.len() - 1].chars();
+
+            while let Some(c) = chars.next() {
+                let c = if c == '\\' {
+                    match chars.next() {
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('n') => '\n',
+                        Some('0') => '\0',
+                        // Unknown escapes are kept as they are
+                        Some(c) => {
+                            objects.push(Rc::new(Self::Character('\\')));
+                            c
+                        }
+                        None => '\\',
+                    }
+                } else {
+                    c
+                };
+
+                objects.push(Rc::new(Self::Character(c)));
+            }
 
             Ok(Self::array_to_pair_list(objects))
+        } else if let Some(s) = string.strip_prefix(':').filter(|s| !s.is_empty()) {
+            Ok(Self::Keyword(s.to_string()))
         } else if !string.is_empty() {
-            Ok(Self::Symbol(string.to_string()))
+            Ok(Self::Symbol(symbols.intern(string)))
         } else {
             Err(LispError::new(
                 LispErrorKind::Parser,
@@ -60,6 +382,27 @@ impl Object {
         }
     }
 
+    // Characters are either given directly, by name or by their unicode value
+    fn parse_character(string: &str) -> Option<char> {
+        let mut chars = string.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => match string {
+                "space" => Some(' '),
+                "newline" => Some('\n'),
+                "tab" => Some('\t'),
+                "return" => Some('\r'),
+                "nul" => Some('\0'),
+                s => s
+                    .strip_prefix("u{")
+                    .and_then(|s| s.strip_suffix('}'))
+                    .and_then(|s| u32::from_str_radix(s, 16).ok())
+                    .and_then(char::from_u32),
+            },
+        }
+    }
+
     pub fn string_to_lisp_string(string: &str) -> Self {
         let objects = string.chars().map(Self::Character).map(Rc::new).collect();
 
@@ -68,120 +411,116 @@ impl Object {
 
     #[allow(clippy::result_unit_err)]
     pub fn pair_list_to_string(&self) -> Result<String, ()> {
-        let mut string = String::new();
-        let mut cur_object = self;
+        let objects = self.pair_list_to_vec().map_err(|_| ())?;
 
-        loop {
-            match cur_object {
-                Object::Pair(a, b) => {
-                    if let Self::Character(c) = **a {
-                        string.push(c);
+        objects
+            .iter()
+            .map(|o| match **o {
+                Self::Character(c) => Ok(c),
+                _ => Err(()),
+            })
+            .collect()
+    }
 
-                        cur_object = b;
-                    } else {
-                        return Err(());
-                    }
-                }
-                Object::Nil => break,
-                _ => return Err(()),
-            }
+    pub(crate) fn array_to_pair_list(array: Vec<Rc<Object>>) -> Self {
+        Self::array_to_dotted_list(array, Self::Nil)
+    }
+
+    // Build a list ending with the given object instead of nil
+    pub(crate) fn array_to_dotted_list(array: Vec<Rc<Object>>, tail: Object) -> Self {
+        let mut ret = tail;
+
+        for i in array.into_iter().rev() {
+            ret = Self::pair(i, Rc::new(ret));
         }
 
-        Ok(string)
+        ret
     }
 
-    fn append_to_pair_list(&mut self, appende: Object) {
-        let mut cur_object: &mut Self = match self {
-            Self::Pair(_, b) => Rc::get_mut(b).unwrap(),
-            _ => panic!("Not a list"),
+    // Collect the items of a proper list, an empty list being nil
+    pub(crate) fn pair_list_to_vec(&self) -> Result<Vec<Rc<Object>>, ArgumentsError> {
+        let (mut objects, mut cur_object) = match self {
+            Self::Pair(a, b) => (vec![a.get()], b.get()),
+            Self::Nil => return Ok(Vec::new()),
+            _ => return Err(ArgumentsError::WrongType),
         };
 
+        // Follows the list at half the speed, so it is caught up with if the list loops
+        let mut slow = Rc::clone(&cur_object);
+
         loop {
-            match cur_object {
-                Self::Pair(_, b) => cur_object = Rc::get_mut(b).unwrap(),
-                Self::Nil => break,
-                _ => panic!("Not a list"),
-            }
-        }
+            let next = match &*cur_object {
+                Self::Pair(a, b) => {
+                    objects.push(a.get());
 
-        *cur_object = appende;
-    }
+                    b.get()
+                }
+                Self::Nil => break Ok(objects),
+                _ => break Err(ArgumentsError::DottedPair),
+            };
 
-    fn array_to_pair_list(array: Vec<Rc<Object>>) -> Self {
-        let mut ret = Self::Pair(array[0].clone(), Rc::new(Self::Nil));
+            cur_object = next;
 
-        let mut iter = array.into_iter();
-        iter.next();
+            if objects.len() % 2 == 1 {
+                let next = match &*slow {
+                    Self::Pair(_, b) => b.get(),
+                    _ => unreachable!(),
+                };
 
-        for i in iter {
-            ret.append_to_pair_list(Self::Pair(i.clone(), Rc::new(Self::Nil)));
-        }
+                slow = next;
+            }
 
-        ret
+            // Circular lists never end, so can't be proper lists
+            if Rc::ptr_eq(&slow, &cur_object) {
+                break Err(ArgumentsError::DottedPair);
+            }
+        }
     }
 
-    fn iter_to_object(strings: &mut dyn Iterator<Item = String>) -> Result<Object, LispError> {
+    fn iter_to_object(
+        strings: &mut dyn Iterator<Item = String>,
+        symbols: &mut SymbolTable,
+        labels: &mut Labels,
+    ) -> Result<Rc<Object>, LispError> {
         let mut list = Vec::new();
-        let mut dot_occured = false;
 
-        Ok(loop {
+        loop {
             match strings.next() {
                 Some(s) => match s.as_str() {
-                    "(" => {
-                        if dot_occured {
-                            let mut list = Self::array_to_pair_list(list);
+                    ")" => break Ok(Rc::new(Self::array_to_pair_list(list))),
+                    "." if !list.is_empty() => {
+                        let mut tail = Self::next_object(strings, symbols, labels)?;
 
-                            list.append_to_pair_list(Self::iter_to_object(strings)?);
-
-                            let next = strings.next().unwrap();
-                            if next != *")" {
-                                return Err(LispError::new(
-                                    LispErrorKind::Parser,
-                                    ParserError::InvalidToken(next),
-                                ));
+                        // Only the closing bracket may come after the tail
+                        loop {
+                            match strings.next() {
+                                Some(s) if s == ")" => break,
+                                Some(s) if s.starts_with(';') => (),
+                                Some(s) => {
+                                    return Err(LispError::new(
+                                        LispErrorKind::Parser,
+                                        ParserError::InvalidToken(s),
+                                    ))
+                                }
+                                None => {
+                                    return Err(LispError::new(
+                                        LispErrorKind::Parser,
+                                        ParserError::UnmatchedToken('('),
+                                    ))
+                                }
                             }
-
-                            break list;
-                        } else {
-                            list.push(Rc::new(Self::iter_to_object(strings)?))
-                        }
-                    }
-                    ")" => {
-                        break if list.is_empty() {
-                            Self::Nil
-                        } else {
-                            Self::array_to_pair_list(list)
                         }
-                    }
-                    "." => {
-                        if !list.is_empty() {
-                            dot_occured = true
-                        } else {
-                            return Err(LispError::new(
-                                LispErrorKind::Parser,
-                                ParserError::InvalidToken(".".to_string()),
-                            ));
+
+                        // The tail may be labelled, so is kept as the object it was read as
+                        for o in list.into_iter().rev() {
+                            tail = Rc::new(Self::pair(o, tail));
                         }
+
+                        break Ok(tail);
                     }
                     s => {
-                        if dot_occured {
-                            if strings.next() == Some(")".to_string()) {
-                                let mut list = Self::array_to_pair_list(list);
-
-                                // Ignore comments
-                                if !s.starts_with(';') {
-                                    list.append_to_pair_list(Object::parse_atom(s)?);
-                                }
-
-                                break list;
-                            } else {
-                                return Err(LispError::new(
-                                    LispErrorKind::Parser,
-                                    ParserError::InvalidToken(s.to_string()),
-                                ));
-                            }
-                        } else if let Some(o) = Self::parse_string(s, strings)? {
-                            list.push(Rc::new(o))
+                        if let Some(o) = Self::parse_string(s, strings, symbols, labels)? {
+                            list.push(o)
                         }
                     }
                 },
@@ -192,15 +531,63 @@ impl Object {
                     ))
                 }
             }
-        })
+        }
+    }
+
+    // Parse the next object within a list, skipping any comments
+    fn next_object(
+        strings: &mut dyn Iterator<Item = String>,
+        symbols: &mut SymbolTable,
+        labels: &mut Labels,
+    ) -> Result<Rc<Object>, LispError> {
+        loop {
+            match strings.next() {
+                Some(s) if s == ")" => {
+                    return Err(LispError::new(
+                        LispErrorKind::Parser,
+                        ParserError::InvalidToken(s),
+                    ))
+                }
+                Some(s) => {
+                    if let Some(o) = Self::parse_string(&s, strings, symbols, labels)? {
+                        return Ok(o);
+                    }
+                }
+                None => {
+                    return Err(LispError::new(
+                        LispErrorKind::Parser,
+                        ParserError::UnmatchedToken('('),
+                    ))
+                }
+            }
+        }
     }
 
     fn parse_string(
         string: &str,
         iter: &mut dyn Iterator<Item = String>,
-    ) -> Result<Option<Object>, LispError> {
+        symbols: &mut SymbolTable,
+        labels: &mut Labels,
+    ) -> Result<Option<Rc<Object>>, LispError> {
+        if let Some((n, rest)) = Self::parse_label(string) {
+            return match rest {
+                "#" => match labels.get(&n) {
+                    Some(o) => Ok(Some(Rc::clone(o))),
+                    None => Err(LispError::new(
+                        LispErrorKind::Parser,
+                        ParserError::InvalidToken(string.to_string()),
+                    )),
+                },
+                _ => {
+                    let rest = rest.strip_prefix('=').unwrap_or_default();
+
+                    Self::parse_labelled(n, rest, iter, symbols, labels).map(Some)
+                }
+            };
+        }
+
         match string {
-            "(" => Ok(Some(Self::iter_to_object(iter)?)),
+            "(" => Ok(Some(Self::iter_to_object(iter, symbols, labels)?)),
             ")" => Err(LispError::new(
                 LispErrorKind::Parser,
                 ParserError::UnmatchedToken(')'),
@@ -209,10 +596,65 @@ impl Object {
                 LispErrorKind::Parser,
                 ParserError::InvalidToken(".".to_string()),
             )),
+            "#" => match iter.next() {
+                // Vectors are written as a list following a hash
+                Some(s) if s == "(" => {
+                    match Self::iter_to_object(iter, symbols, labels)?.pair_list_to_vec() {
+                        Ok(objects) => Ok(Some(Rc::new(Object::Vector(RefCell::new(objects))))),
+                        Err(_) => Err(LispError::new(
+                            LispErrorKind::Parser,
+                            ParserError::InvalidToken(".".to_string()),
+                        )),
+                    }
+                }
+                _ => Err(LispError::new(
+                    LispErrorKind::Parser,
+                    ParserError::InvalidToken("#".to_string()),
+                )),
+            },
+            "#hash" => match iter.next() {
+                // Hash tables are written as a list of key value pairs following #hash
+                Some(s) if s == "(" => {
+                    let mut table = HashTable::new();
+                    let pairs =
+                        match Self::iter_to_object(iter, symbols, labels)?.pair_list_to_vec() {
+                            Ok(pairs) => pairs,
+                            Err(_) => {
+                                return Err(LispError::new(
+                                    LispErrorKind::Parser,
+                                    ParserError::InvalidToken(".".to_string()),
+                                ))
+                            }
+                        };
+
+                    for pair in pairs {
+                        let key = match &*pair {
+                            Object::Pair(k, v) => table.key(k.get()).map(|k| (k, v.get())),
+                            _ => Err(ArgumentsError::WrongType),
+                        };
+
+                        match key {
+                            Ok((k, v)) => table.insert(k, v),
+                            Err(_) => {
+                                return Err(LispError::new(
+                                    LispErrorKind::Parser,
+                                    ParserError::UnparsableAtom(pair.to_string()),
+                                ))
+                            }
+                        };
+                    }
+
+                    Ok(Some(Rc::new(Object::HashTable(RefCell::new(table)))))
+                }
+                _ => Err(LispError::new(
+                    LispErrorKind::Parser,
+                    ParserError::InvalidToken("#hash".to_string()),
+                )),
+            },
             "\'" => {
                 if let Some(next) = iter.next() {
-                    if let Some(next) = Self::parse_string(&next, iter)? {
-                        Ok(Some(Object::Quoted(Rc::new(next))))
+                    if let Some(next) = Self::parse_string(&next, iter, symbols, labels)? {
+                        Ok(Some(Rc::new(Object::Quoted(next))))
                     } else {
                         Err(LispError::new(
                             LispErrorKind::Parser,
@@ -229,7 +671,7 @@ impl Object {
             s => {
                 // Ignore comments
                 if !s.starts_with(';') {
-                    Ok(Some(Object::parse_atom(s)?))
+                    Ok(Some(Rc::new(Object::parse_atom(s, symbols)?)))
                 } else {
                     Ok(None)
                 }
@@ -237,14 +679,18 @@ impl Object {
         }
     }
 
-    fn eval_strings(strings: Vec<String>) -> Result<Vec<Object>, LispError> {
+    fn eval_strings(
+        strings: Vec<String>,
+        symbols: &mut SymbolTable,
+    ) -> Result<Vec<Rc<Object>>, LispError> {
         let mut iter = strings.into_iter();
+        let mut labels = Labels::new();
         let mut ret = Vec::new();
 
         loop {
             match iter.next() {
                 Some(s) => {
-                    if let Some(o) = Self::parse_string(&s, &mut iter)? {
+                    if let Some(o) = Self::parse_string(&s, &mut iter, symbols, &mut labels)? {
                         ret.push(o)
                     }
                 }
@@ -253,6 +699,129 @@ impl Object {
         }
     }
 
+    // Labels are written as #n= before an object and #n# where it is referred to again
+    fn parse_label(string: &str) -> Option<(usize, &str)> {
+        let digits = string.strip_prefix('#')?;
+        let end = digits.find(|c: char| !c.is_ascii_digit())?;
+        let rest = &digits[end..];
+
+        if end == 0 || !(rest == "#" || rest.starts_with('=')) {
+            return None;
+        }
+
+        Some((digits[..end].parse().ok()?, rest))
+    }
+
+    // Read the object a label is given to. Until it has been read, references to the label are
+    // to a placeholder, which is then replaced by the object wherever it was put
+    fn parse_labelled(
+        n: usize,
+        rest: &str,
+        iter: &mut dyn Iterator<Item = String>,
+        symbols: &mut SymbolTable,
+        labels: &mut Labels,
+    ) -> Result<Rc<Object>, LispError> {
+        let invalid = || {
+            LispError::new(
+                LispErrorKind::Parser,
+                ParserError::InvalidToken(format!("#{}=", n)),
+            )
+        };
+
+        let placeholder = Rc::new(Object::Nil);
+        labels.insert(n, Rc::clone(&placeholder));
+
+        // The object may be written straight after the label, without a space
+        let object = match rest {
+            "" => Self::next_object(iter, symbols, labels)?,
+            rest => Self::parse_string(rest, iter, symbols, labels)?.ok_or_else(invalid)?,
+        };
+
+        if Rc::ptr_eq(&object, &placeholder) {
+            return Err(invalid());
+        }
+
+        object
+            .replace_placeholder(&placeholder)
+            .map_err(|_| invalid())?;
+        labels.insert(n, Rc::clone(&object));
+
+        Ok(object)
+    }
+
+    // Replace a label's placeholder with the object it labels. Tables are filled again once
+    // everything within them has been replaced, as their keys are hashed by what they contain
+    fn replace_placeholder(
+        self: &Rc<Self>,
+        placeholder: &Rc<Object>,
+    ) -> Result<(), ArgumentsError> {
+        let replace = |o: &Rc<Object>| match Rc::ptr_eq(o, placeholder) {
+            true => Rc::clone(self),
+            false => Rc::clone(o),
+        };
+
+        let mut seen = HashSet::new();
+        let mut tables = Vec::new();
+        let mut objects = vec![Rc::clone(self)];
+
+        while let Some(o) = objects.pop() {
+            if !seen.insert(Rc::as_ptr(&o)) {
+                continue;
+            }
+
+            match &*o {
+                Self::Pair(a, b) => {
+                    for slot in [a, b] {
+                        let object = replace(&slot.get());
+
+                        slot.set(Rc::clone(&object));
+                        objects.push(object);
+                    }
+                }
+                Self::Vector(v) => {
+                    for item in v.borrow_mut().iter_mut() {
+                        *item = replace(item);
+                        objects.push(Rc::clone(item));
+                    }
+                }
+                Self::HashTable(t) => {
+                    for (k, v) in t.borrow().iter() {
+                        objects.push(Rc::clone(k));
+                        objects.push(Rc::clone(v));
+                    }
+
+                    tables.push(Rc::clone(&o));
+                }
+                // Quoted objects can't be changed, so can't refer to what contains them
+                Self::Quoted(q) if Rc::ptr_eq(q, placeholder) => {
+                    return Err(ArgumentsError::WrongType)
+                }
+                Self::Quoted(q) => objects.push(Rc::clone(q)),
+                _ => (),
+            }
+        }
+
+        for table in tables {
+            if let Self::HashTable(t) = &*table {
+                let entries: Vec<_> = t
+                    .borrow()
+                    .iter()
+                    .map(|(k, v)| (replace(k), replace(v)))
+                    .collect();
+                let mut filled = HashTable::new();
+
+                for (k, v) in entries {
+                    let key = filled.key(k)?;
+                    filled.insert(key, v);
+                }
+
+                *t.borrow_mut() = filled;
+            }
+        }
+
+        Ok(())
+    }
+
     fn split_into_strings(input: &str) -> Vec<String> {
         let regex = Regex::new(r#"(?m);[^\n]*|"(?:\\.|[^"\\])*"|'|\(|\)|[^\s()]*"#).unwrap();
 
@@ -270,133 +839,648 @@ impl Object {
             .collect()
     }
 
-    pub fn eval(input: &str) -> Result<Vec<Object>, LispError> {
+    // Read objects from a string, interning symbols into the given table
+    pub fn read(input: &str, symbols: &mut SymbolTable) -> Result<Vec<Rc<Object>>, LispError> {
         let strings = Self::split_into_strings(input);
-        Self::eval_strings(strings)
+        Self::eval_strings(strings, symbols)
+    }
+
+    // Symbols read like this are numbered by a table of their own, so an interpreter has to find
+    // each of them again by name
+    #[deprecated(note = "use `Lisp::read`, or `Object::read` with the interpreter's symbols")]
+    pub fn eval(input: &str) -> Result<Vec<Object>, LispError> {
+        Self::read(input, &mut SymbolTable::new())?
+            .into_iter()
+            .map(|o| {
+                // Objects which refer to themselves can't be given back without a pointer to them
+                Rc::try_unwrap(o).map_err(|_| {
+                    LispError::new(
+                        LispErrorKind::Parser,
+                        ParserError::InvalidToken("#0=".to_string()),
+                    )
+                })
+            })
+            .collect()
     }
 }
 
 use core::fmt;
-impl fmt::Display for Object {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Object {
+    // Find the objects which contain themselves, which are labelled when written so writing them ends
+    fn find_cycles(
+        &self,
+        seen: &mut HashSet<*const Object>,
+        path: &mut HashSet<*const Object>,
+        cycles: &mut HashSet<*const Object>,
+    ) {
+        // The pairs of a list stay on the path until the whole list has been looked through
+        let mut list = Vec::new();
+        let mut next = self.find_cycles_in(seen, path, cycles, &mut list);
+
+        while let Some(o) = next {
+            next = o.find_cycles_in(seen, path, cycles, &mut list);
+        }
+
+        for o in list {
+            path.remove(&o);
+        }
+    }
+
+    // Look through the items of one object, giving the rest of the list if it is a pair
+    fn find_cycles_in(
+        &self,
+        seen: &mut HashSet<*const Object>,
+        path: &mut HashSet<*const Object>,
+        cycles: &mut HashSet<*const Object>,
+        list: &mut Vec<*const Object>,
+    ) -> Option<Rc<Object>> {
+        let ptr = self as *const Object;
+
+        if let Self::Pair(_, _) | Self::Vector(_) | Self::HashTable(_) = self {
+            if !seen.insert(ptr) {
+                if path.contains(&ptr) {
+                    cycles.insert(ptr);
+                }
+
+                return None;
+            }
+
+            path.insert(ptr);
+            list.push(ptr);
+        }
+
         match self {
             Self::Pair(a, b) => {
-                let mut string = true;
-                let mut objects = Vec::new();
-                let mut cur_object = self;
+                a.get().find_cycles(seen, path, cycles);
 
-                loop {
-                    match cur_object {
-                        Self::Pair(a, b) => {
-                            objects.push(a);
+                Some(b.get())
+            }
+            Self::Vector(v) => {
+                for o in v.borrow().iter() {
+                    o.find_cycles(seen, path, cycles);
+                }
 
-                            match **a {
-                                Self::Character(_) => (),
-                                _ => string = false,
-                            }
+                None
+            }
+            Self::HashTable(t) => {
+                for (k, v) in t.borrow().iter() {
+                    k.find_cycles(seen, path, cycles);
+                    v.find_cycles(seen, path, cycles);
+                }
 
-                            cur_object = b;
-                        }
-                        Self::Nil => break,
-                        c => {
-                            return if objects.is_empty() {
-                                write!(f, "({} . {})", a, b)
-                            } else {
-                                write!(f, "(")?;
+                None
+            }
+            Self::Quoted(o) => Some(Rc::clone(o)),
+            _ => None,
+        }
+    }
 
-                                let mut objects = objects.iter();
+    // Write an object, either so it can be read back in or for people to read
+    fn write(&self, f: &mut fmt::Formatter<'_>, readable: bool) -> fmt::Result {
+        let mut cycles = HashSet::new();
 
-                                if let Some(o) = objects.next() {
-                                    write!(f, "{}", o)?;
+        self.find_cycles(&mut HashSet::new(), &mut HashSet::new(), &mut cycles);
 
-                                    for o in objects {
-                                        write!(f, " {}", o)?;
-                                    }
-                                }
+        self.write_labelled(f, readable, &cycles, &mut HashMap::new())
+    }
 
-                                write!(f, " . {})", c)
-                            }
+    // Circular objects are labelled with #n= the first time they are written, and #n# after
+    fn write_labelled(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        readable: bool,
+        cycles: &HashSet<*const Object>,
+        labels: &mut HashMap<*const Object, usize>,
+    ) -> fmt::Result {
+        let ptr = self as *const Object;
+
+        if cycles.contains(&ptr) {
+            if let Some(n) = labels.get(&ptr) {
+                return write!(f, "#{}#", n);
+            }
+
+            let n = labels.len();
+            labels.insert(ptr, n);
+
+            write!(f, "#{}=", n)?;
+        }
+
+        match self {
+            Self::Pair(a, b) => {
+                let mut objects = vec![a.get()];
+                let mut cur_object = b.get();
+
+                // A labelled pair within the list is written as its tail, so the list ends
+                let tail = loop {
+                    let next = match &*cur_object {
+                        Self::Pair(a, b) if !cycles.contains(&Rc::as_ptr(&cur_object)) => {
+                            objects.push(a.get());
+
+                            b.get()
                         }
-                    }
-                }
+                        Self::Nil => break None,
+                        _ => break Some(cur_object),
+                    };
+
+                    cur_object = next;
+                };
+
+                // Nothing marks a list as a string, so any list of characters is written as one
+                let string =
+                    tail.is_none() && objects.iter().all(|o| matches!(**o, Self::Character(_)));
 
                 if string {
-                    let mut string = String::new();
+                    if readable {
+                        write!(f, "\"")?;
+                    }
 
                     for o in objects {
-                        if let Self::Character(c) = **o {
+                        if let Self::Character(c) = *o {
                             match c {
-                                '\\' => string.push_str("\\\\"),
-                                '\"' => string.push_str("\\\n"),
-                                '\t' => string.push_str("\\t"),
-                                '\r' => string.push_str("\\r"),
-                                '\n' => string.push_str("\\n"),
-                                '\0' => string.push_str("\\0"),
-                                c => string.push(c),
+                                '\\' if readable => write!(f, "\\\\")?,
+                                '\"' if readable => write!(f, "\\\"")?,
+                                '\t' if readable => write!(f, "\\t")?,
+                                '\r' if readable => write!(f, "\\r")?,
+                                '\n' if readable => write!(f, "\\n")?,
+                                '\0' if readable => write!(f, "\\0")?,
+                                c => write!(f, "{}", c)?,
                             }
-                        } else {
-                            panic!("Unexpected type in string");
                         }
                     }
 
-                    write!(f, "\"{}\"", string)
+                    if readable {
+                        write!(f, "\"")?;
+                    }
+
+                    Ok(())
                 } else {
                     write!(f, "(")?;
 
                     let mut objects = objects.iter();
 
                     if let Some(o) = objects.next() {
-                        write!(f, "{}", o)?;
+                        o.write_labelled(f, readable, cycles, labels)?;
 
                         for o in objects {
-                            write!(f, " {}", o)?;
+                            write!(f, " ")?;
+                            o.write_labelled(f, readable, cycles, labels)?;
                         }
                     }
 
+                    if let Some(c) = tail {
+                        write!(f, " . ")?;
+                        c.write_labelled(f, readable, cycles, labels)?;
+                    }
+
                     write!(f, ")")
                 }
             }
             Self::Number(i) => write!(f, "{}", i),
-            Self::Character(c) => write!(f, "\\{}", c),
+            Self::Character(c) if !readable => write!(f, "{}", c),
+            Self::Character(c) => match c {
+                ' ' => write!(f, "\\space"),
+                '\n' => write!(f, "\\newline"),
+                '\t' => write!(f, "\\tab"),
+                '\r' => write!(f, "\\return"),
+                '\0' => write!(f, "\\nul"),
+                // Characters the reader would split on, or that can't be seen
+                '(' | ')' => write!(f, "\\u{{{:x}}}", *c as u32),
+                c if c.is_whitespace() || c.is_control() => write!(f, "\\u{{{:x}}}", *c as u32),
+                c => write!(f, "\\{}", c),
+            },
             Self::Symbol(s) => write!(f, "{}", s),
-            Self::Quoted(o) => write!(f, "'{}", o),
+            Self::Keyword(s) => write!(f, ":{}", s),
+            Self::Quoted(o) => {
+                write!(f, "'")?;
+                o.write_labelled(f, readable, cycles, labels)
+            }
+            // Also the empty string
             Self::Nil => write!(f, "()"),
             Self::True => write!(f, "t"),
             Self::RustFunc(x) => write!(f, "{:p}", x),
-            Self::LispFunc(a, _) => write!(f, "({})", a.join(" ")),
-            Self::RustType(t) => write!(f, "{:?}", t),
+            Self::RustClosure(x) => write!(f, "{:p}", Rc::as_ptr(x) as *const ()),
+            Self::LispFunc(func) => {
+                let mut names: Vec<&str> = func.params.iter().map(Symbol::name).collect();
+
+                if let Some(keys) = func.keys {
+                    names.insert(keys, "&key");
+                }
+
+                write!(f, "({})", names.join(" "))
+            }
+            Self::RustType(t) => write!(f, "{}", t),
+            Self::Vector(v) => {
+                write!(f, "#(")?;
+
+                let objects = v.borrow();
+                let mut objects = objects.iter();
+
+                if let Some(o) = objects.next() {
+                    o.write_labelled(f, readable, cycles, labels)?;
+
+                    for o in objects {
+                        write!(f, " ")?;
+                        o.write_labelled(f, readable, cycles, labels)?;
+                    }
+                }
+
+                write!(f, ")")
+            }
+            Self::HashTable(t) => {
+                write!(f, "#hash(")?;
+
+                for (i, (k, v)) in t.borrow().iter().enumerate() {
+                    if i != 0 {
+                        write!(f, " ")?;
+                    }
+
+                    write!(f, "(")?;
+                    k.write_labelled(f, readable, cycles, labels)?;
+                    write!(f, " . ")?;
+                    v.write_labelled(f, readable, cycles, labels)?;
+                    write!(f, ")")?;
+                }
+
+                write!(f, ")")
+            }
+        }
+    }
+
+    // Format an object for people to read, without quotes around strings or escaped characters
+    pub fn display(&self) -> DisplayObject<'_> {
+        DisplayObject(self)
+    }
+}
+
+// Formats an object so it reads back in as the same value
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, true)
+    }
+}
+
+// Formats an object for people to read, created by Object::display
+pub struct DisplayObject<'a>(&'a Object);
+
+impl fmt::Display for DisplayObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.write(f, false)
+    }
+}
+
+impl Object {
+    // Identity, where objects are only the same if they are one object, apart from symbols,
+    // keywords, nil and t which are the same whenever they look the same
+    pub fn identical(this: &Rc<Self>, other: &Rc<Self>) -> bool {
+        match (&**this, &**other) {
+            (Self::Nil, Self::Nil) | (Self::True, Self::True) => true,
+            (Self::Symbol(s), Self::Symbol(o)) => s == o,
+            (Self::Keyword(s), Self::Keyword(o)) => s == o,
+            _ => Rc::ptr_eq(this, other),
+        }
+    }
+
+    // Identity for lists and functions, but the value for numbers and characters
+    pub fn eqv(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Nil, Self::Nil) | (Self::True, Self::True) => true,
+            (Self::Symbol(s), Self::Symbol(o)) => s == o,
+            (Self::Keyword(s), Self::Keyword(o)) => s == o,
+            (Self::Number(i), Self::Number(o)) => i == o,
+            (Self::Character(c), Self::Character(o)) => c == o,
+            _ => core::ptr::eq(self, other),
         }
     }
 }
 
+// Deep structural equality, functions being equal only to themselves and rust types unless they
+// say otherwise
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
-        match self {
-            Self::Pair(a, b) => match other {
-                Self::Pair(c, d) => a == c && b == d,
-                _ => false,
-            },
-            Self::Number(i) => match other {
-                Self::Number(o) => i == o,
-                _ => false,
-            },
-            Self::Character(c) => match other {
-                Self::Character(o) => c == o,
-                _ => false,
-            },
-            Self::Symbol(s) => match other {
-                Self::Symbol(o) => s == o,
+        self.equal(other, &mut HashSet::new())
+    }
+}
+
+// The objects labels refer to while reading
+type Labels = HashMap<usize, Rc<Object>>;
+
+// Pairs of containers being compared with each other
+type Compared = HashSet<(*const Object, *const Object)>;
+
+impl Object {
+    // Containers reached again while they are being compared are taken to be equal, as any
+    // difference will be found where they were first reached, so objects which contain themselves
+    // can be compared
+    fn equal(&self, other: &Self, seen: &mut Compared) -> bool {
+        let compared = (self as *const Self, other as *const Self);
+
+        match (self, other) {
+            (Self::Pair(_, _), Self::Pair(_, _)) if !seen.insert(compared) => true,
+            (Self::Pair(a, b), Self::Pair(c, d)) => {
+                a.get().equal(&c.get(), seen) && Self::tail_equal(b.get(), d.get(), seen)
+            }
+            (Self::Number(i), Self::Number(o)) => i == o,
+            (Self::Character(c), Self::Character(o)) => c == o,
+            (Self::Symbol(s), Self::Symbol(o)) => s == o,
+            (Self::Keyword(s), Self::Keyword(o)) => s == o,
+            (Self::Quoted(s), Self::Quoted(o)) => s.equal(o, seen),
+            (Self::Vector(_), Self::Vector(_)) | (Self::HashTable(_), Self::HashTable(_))
+                if core::ptr::eq(self, other) || !seen.insert(compared) =>
+            {
+                true
+            }
+            // A container being changed can't be looked at, so isn't equal to anything else
+            (Self::Vector(v), Self::Vector(o)) => match (v.try_borrow(), o.try_borrow()) {
+                (Ok(v), Ok(o)) => {
+                    v.len() == o.len() && v.iter().zip(o.iter()).all(|(v, o)| v.equal(o, seen))
+                }
                 _ => false,
             },
-            Self::Quoted(s) => match other {
-                Self::Quoted(o) => s == o,
+            (Self::HashTable(t), Self::HashTable(o)) => match (t.try_borrow(), o.try_borrow()) {
+                (Ok(t), Ok(o)) => t.equal(&o, seen),
                 _ => false,
             },
-            Self::RustFunc(_) => false,
-            Self::LispFunc(_, _) => false,
-            Self::Nil => matches!(other, Self::Nil),
-            Self::True => matches!(other, Self::True),
-            Self::RustType(_) => false,
+            (Self::RustFunc(_), _) | (Self::RustClosure(_), _) | (Self::LispFunc(_), _) => {
+                core::ptr::eq(self, other)
+            }
+            (Self::Nil, Self::Nil) | (Self::True, Self::True) => true,
+            (Self::RustType(t), Self::RustType(o)) => core::ptr::eq(self, other) || t == o,
+            _ => false,
         }
     }
+
+    // Lists are followed along rather than recursed into, so long ones don't overflow the stack
+    fn tail_equal(mut this: Rc<Self>, mut other: Rc<Self>, seen: &mut Compared) -> bool {
+        loop {
+            let next = match (&*this, &*other) {
+                (Self::Pair(a, b), Self::Pair(c, d)) => {
+                    if !seen.insert((Rc::as_ptr(&this), Rc::as_ptr(&other))) {
+                        return true;
+                    }
+
+                    if !a.get().equal(&c.get(), seen) {
+                        return false;
+                    }
+
+                    (b.get(), d.get())
+                }
+                _ => return this.equal(&other, seen),
+            };
+
+            (this, other) = next;
+        }
+    }
+}
+
+impl Object {
+    // The hash of an object used as a hash table key, failing for keys which could never be found
+    // again, being NaN or changing as they are hashed
+    pub(crate) fn hash_key(&self) -> Result<u64, ArgumentsError> {
+        #[allow(deprecated)]
+        let mut state = DefaultHasher::default();
+
+        self.try_hash(&mut state, &mut HashSet::new())?;
+
+        Ok(state.finish())
+    }
+
+    // The containers being hashed are kept in path, as one reached again contains itself, which
+    // would never finish hashing
+    fn try_hash<H: Hasher>(
+        &self,
+        state: &mut H,
+        path: &mut HashSet<*const Object>,
+    ) -> Result<(), ArgumentsError> {
+        core::mem::discriminant(self).hash(state);
+
+        match self {
+            Self::Nil | Self::True => (),
+            Self::Pair(a, b) => {
+                // The pairs of a list are followed along, staying on the path until it ends
+                let mut list = vec![self as *const Object];
+
+                if !path.insert(self) {
+                    return Err(ArgumentsError::InvalidKey);
+                }
+
+                a.get().try_hash(state, path)?;
+
+                let mut tail = b.get();
+
+                loop {
+                    let next = match &*tail {
+                        Self::Pair(a, b) => {
+                            core::mem::discriminant(&*tail).hash(state);
+
+                            if !path.insert(Rc::as_ptr(&tail)) {
+                                return Err(ArgumentsError::InvalidKey);
+                            }
+
+                            list.push(Rc::as_ptr(&tail));
+                            a.get().try_hash(state, path)?;
+
+                            b.get()
+                        }
+                        _ => break tail.try_hash(state, path)?,
+                    };
+
+                    tail = next;
+                }
+
+                for p in list {
+                    path.remove(&p);
+                }
+            }
+            Self::Symbol(s) => s.hash(state),
+            Self::Keyword(s) => s.hash(state),
+            // NaN isn't equal to itself, so could never be found
+            Self::Number(n) if n.is_nan() => return Err(ArgumentsError::InvalidKey),
+            // Zero and negative zero are equal, so must hash the same
+            Self::Number(n) if *n == 0.0 => 0u32.hash(state),
+            Self::Number(n) => n.to_bits().hash(state),
+            Self::Character(c) => c.hash(state),
+            Self::Quoted(o) => o.try_hash(state, path)?,
+            Self::Vector(v) => {
+                let v = v.try_borrow().map_err(|_| ArgumentsError::InvalidKey)?;
+
+                if !path.insert(self) {
+                    return Err(ArgumentsError::InvalidKey);
+                }
+
+                v.len().hash(state);
+
+                for o in v.iter() {
+                    o.try_hash(state, path)?;
+                }
+
+                path.remove(&(self as *const Object));
+            }
+            // Tables have no order to hash their entries in, and one used as a key of itself
+            // would change as it was added
+            Self::HashTable(_) => (),
+            Self::RustType(t) => t.hash(state),
+            Self::LispFunc(_) | Self::RustFunc(_) | Self::RustClosure(_) => {
+                (self as *const Self).hash(state)
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Hashing matches equal?, so objects with the same structure hash the same. Objects which can't
+// be hash table keys are hashed as far as they can be
+impl Hash for Object {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let _ = self.try_hash(state, &mut HashSet::new());
+    }
+}
+
+// The entries of a hash table, kept in buckets by the hash of their keys. Keys are found before
+// they are used, so a table can be changed using a key which borrows the table to be compared,
+// as the table itself would
+#[derive(Debug, Default)]
+pub struct HashTable {
+    buckets: HashMap<u64, Bucket, BuildHasherDefault<IdHasher>>,
+    len: usize,
+}
+
+// The keys with the same hash, with their values
+type Bucket = Vec<(Rc<Object>, Rc<Object>)>;
+
+// A key found in a hash table, either the equal key it already has or a new one
+#[derive(Debug, Clone)]
+pub struct HashKey {
+    object: Rc<Object>,
+    hash: u64,
+}
+
+impl HashKey {
+    pub fn object(&self) -> &Rc<Object> {
+        &self.object
+    }
+}
+
+impl HashTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The key an object is stored under, comparing it to the keys already in the table with
+    // equal?, which only needs the table borrowed immutably
+    pub fn key(&self, object: Rc<Object>) -> Result<HashKey, ArgumentsError> {
+        let hash = object.hash_key()?;
+
+        let object = self
+            .find(&object, hash, &mut HashSet::new())
+            .map_or(object, |(k, _)| Rc::clone(k));
+
+        Ok(HashKey { object, hash })
+    }
+
+    // The entry with a key equal to an object. Comparing against a key which turns out to be
+    // different says nothing about what was compared along the way, so that is forgotten
+    fn find(
+        &self,
+        object: &Object,
+        hash: u64,
+        seen: &mut Compared,
+    ) -> Option<&(Rc<Object>, Rc<Object>)> {
+        self.buckets.get(&hash)?.iter().find(|(k, _)| {
+            let mut compared = seen.clone();
+            let equal = k.equal(object, &mut compared);
+
+            if equal {
+                *seen = compared;
+            }
+
+            equal
+        })
+    }
+
+    fn equal(&self, other: &Self, seen: &mut Compared) -> bool {
+        self.len == other.len
+            && self.iter().all(|(k, v)| {
+                k.hash_key()
+                    .ok()
+                    .and_then(|hash| other.find(k, hash, seen))
+                    .is_some_and(|(_, o)| v.equal(o, seen))
+            })
+    }
+
+    // Keys found in this table are the same object as the one in it, so it is found again by
+    // identity, without comparing anything else
+    fn position(&self, key: &HashKey) -> Option<usize> {
+        self.buckets
+            .get(&key.hash)?
+            .iter()
+            .position(|(k, _)| Rc::ptr_eq(k, &key.object))
+    }
+
+    pub fn get(&self, key: &HashKey) -> Option<&Rc<Object>> {
+        let i = self.position(key)?;
+
+        Some(&self.buckets[&key.hash][i].1)
+    }
+
+    // Find the value for an object in one step
+    pub fn lookup(&self, object: Rc<Object>) -> Result<Option<&Rc<Object>>, ArgumentsError> {
+        Ok(self.get(&self.key(object)?))
+    }
+
+    // Set the value of a key, giving the value it had
+    pub fn insert(&mut self, key: HashKey, value: Rc<Object>) -> Option<Rc<Object>> {
+        if let Some(i) = self.position(&key) {
+            let entry = &mut self.buckets.get_mut(&key.hash)?[i];
+
+            return Some(core::mem::replace(&mut entry.1, value));
+        }
+
+        self.buckets
+            .entry(key.hash)
+            .or_default()
+            .push((key.object, value));
+        self.len += 1;
+
+        None
+    }
+
+    pub fn remove(&mut self, key: &HashKey) -> Option<Rc<Object>> {
+        let i = self.position(key)?;
+        let bucket = self.buckets.get_mut(&key.hash)?;
+        let (_, value) = bucket.swap_remove(i);
+
+        if bucket.is_empty() {
+            self.buckets.remove(&key.hash);
+        }
+
+        self.len -= 1;
+
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.len = 0;
+    }
+
+    // Every key and value, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<Object>, &Rc<Object>)> {
+        self.buckets.values().flatten().map(|(k, v)| (k, v))
+    }
+}
+
+// Tables are equal if they have equal keys with equal values
+impl PartialEq for HashTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.equal(other, &mut HashSet::new())
+    }
 }
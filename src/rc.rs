@@ -0,0 +1,242 @@
+// The pointers and cells objects are built from. With the sync feature they are atomic and
+// locked, so an interpreter can be sent to another thread, at the cost of being slower
+
+use core::any::Any;
+
+#[cfg(not(feature = "sync"))]
+pub use alloc::rc::{Rc, Weak};
+
+#[cfg(not(feature = "sync"))]
+pub use core::cell::{OnceCell, RefCell};
+
+#[cfg(feature = "sync")]
+pub use alloc::sync::{Arc as Rc, Weak};
+
+#[cfg(feature = "sync")]
+pub use std::sync::OnceLock as OnceCell;
+
+#[cfg(feature = "sync")]
+pub use lock::RefCell;
+
+// Bounds on rust values held by objects, which have to be safe to share between threads with sync
+#[cfg(not(feature = "sync"))]
+pub trait Shareable {}
+
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> Shareable for T {}
+
+#[cfg(feature = "sync")]
+pub trait Shareable: Send + Sync {}
+
+#[cfg(feature = "sync")]
+impl<T: ?Sized + Send + Sync> Shareable for T {}
+
+// A rust value held by an object, which has to be safe to share between threads with sync
+#[cfg(not(feature = "sync"))]
+pub type AnyValue = dyn Any;
+
+#[cfg(feature = "sync")]
+pub type AnyValue = dyn Any + Send + Sync;
+
+#[cfg(feature = "sync")]
+mod lock {
+    use std::{
+        cell::UnsafeCell,
+        fmt,
+        ops::{Deref, DerefMut},
+        sync::{Condvar, Mutex, MutexGuard, PoisonError},
+        vec::Vec,
+    };
+
+    std::thread_local! {
+        static THREAD: u8 = const { 0 };
+    }
+
+    // An address which is different for every running thread
+    fn thread() -> usize {
+        THREAD.with(|t| t as *const u8 as usize)
+    }
+
+    // The threads holding a lock, either any number reading it, or one changing it
+    #[derive(Default)]
+    struct Holders {
+        // Each thread reading, with how many times it is
+        readers: Vec<(usize, usize)>,
+        writer: Option<usize>,
+    }
+
+    // A lock with the methods of a RefCell, so the rest of the interpreter doesn't need to know
+    // which it is using. Borrowing waits while another thread holds it, but panics when the same
+    // thread already does, as a RefCell would, rather than waiting forever. A panic while it is
+    // held doesn't stop it being used afterwards, also the same as a RefCell
+    pub struct RefCell<T> {
+        holders: Mutex<Holders>,
+        released: Condvar,
+        value: UnsafeCell<T>,
+    }
+
+    // The value is only reached through the guards, which the holders make sure are either all
+    // reading it or the only one changing it, the same as a RwLock
+    unsafe impl<T: Send> Send for RefCell<T> {}
+    unsafe impl<T: Send + Sync> Sync for RefCell<T> {}
+
+    impl<T> RefCell<T> {
+        pub const fn new(value: T) -> Self {
+            Self {
+                holders: Mutex::new(Holders {
+                    readers: Vec::new(),
+                    writer: None,
+                }),
+                released: Condvar::new(),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        // The bookkeeping is never left half changed, so a panic while it was held doesn't matter
+        fn holders(&self) -> MutexGuard<'_, Holders> {
+            self.holders.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        fn wait<'a>(&self, holders: MutexGuard<'a, Holders>) -> MutexGuard<'a, Holders> {
+            self.released
+                .wait(holders)
+                .unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub fn borrow(&self) -> Ref<'_, T> {
+            match self.try_borrow() {
+                Ok(guard) => guard,
+                Err(BorrowError) => panic!("already mutably borrowed"),
+            }
+        }
+
+        pub fn borrow_mut(&self) -> RefMut<'_, T> {
+            let thread = thread();
+            let mut holders = self.holders();
+
+            loop {
+                if holders.writer == Some(thread) || holders.readers.iter().any(|r| r.0 == thread) {
+                    panic!("already borrowed");
+                }
+
+                if holders.writer.is_none() && holders.readers.is_empty() {
+                    break;
+                }
+
+                holders = self.wait(holders);
+            }
+
+            holders.writer = Some(thread);
+
+            RefMut { cell: self }
+        }
+
+        // Fail rather than panic while this thread is changing it
+        pub fn try_borrow(&self) -> Result<Ref<'_, T>, BorrowError> {
+            let thread = thread();
+            let mut holders = self.holders();
+
+            while let Some(writer) = holders.writer {
+                if writer == thread {
+                    return Err(BorrowError);
+                }
+
+                holders = self.wait(holders);
+            }
+
+            match holders.readers.iter_mut().find(|r| r.0 == thread) {
+                Some(r) => r.1 += 1,
+                None => holders.readers.push((thread, 1)),
+            }
+
+            Ok(Ref { cell: self, thread })
+        }
+    }
+
+    impl<T: Default> Default for RefCell<T> {
+        fn default() -> Self {
+            Self::new(T::default())
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for RefCell<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let mut d = f.debug_struct("RefCell");
+
+            match self.try_borrow() {
+                Ok(value) => d.field("value", &*value),
+                Err(BorrowError) => d.field("value", &format_args!("<borrowed>")),
+            };
+
+            d.finish()
+        }
+    }
+
+    pub struct Ref<'a, T> {
+        cell: &'a RefCell<T>,
+        // The thread which borrowed it, in case it is dropped by another
+        thread: usize,
+    }
+
+    impl<T> Deref for Ref<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            unsafe { &*self.cell.value.get() }
+        }
+    }
+
+    impl<T> Drop for Ref<'_, T> {
+        fn drop(&mut self) {
+            let mut holders = self.cell.holders();
+
+            if let Some(i) = holders.readers.iter().position(|r| r.0 == self.thread) {
+                holders.readers[i].1 -= 1;
+
+                if holders.readers[i].1 == 0 {
+                    holders.readers.swap_remove(i);
+                }
+            }
+
+            if holders.readers.is_empty() {
+                self.cell.released.notify_all();
+            }
+        }
+    }
+
+    pub struct RefMut<'a, T> {
+        cell: &'a RefCell<T>,
+    }
+
+    impl<T> Deref for RefMut<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            unsafe { &*self.cell.value.get() }
+        }
+    }
+
+    impl<T> DerefMut for RefMut<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            unsafe { &mut *self.cell.value.get() }
+        }
+    }
+
+    impl<T> Drop for RefMut<'_, T> {
+        fn drop(&mut self) {
+            self.cell.holders().writer = None;
+            self.cell.released.notify_all();
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct BorrowError;
+}
+
+// Fail to build if anything stops an interpreter being sent between threads
+#[cfg(feature = "sync")]
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<crate::lisp::Lisp>();
+};
@@ -0,0 +1,156 @@
+// Symbols are interned, so finding a variable only needs their number rather than their name
+
+use alloc::vec::Vec;
+
+use core::{
+    fmt,
+    hash::{BuildHasherDefault, Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+
+use crate::rc::Rc;
+
+// A name given a number by a symbol table, which is only meaningful to the table that gave it
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    id: usize,
+    name: Rc<str>,
+    // The table which gave it
+    table: usize,
+}
+
+impl Symbol {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn table(&self) -> usize {
+        self.table
+    }
+}
+
+// Symbols from the same table are equal when their numbers are
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.table == other.table
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+// Every symbol name seen by an interpreter, shared with the interpreters created from it
+#[derive(Debug)]
+pub struct SymbolTable {
+    ids: HashMap<Rc<str>, usize>,
+    names: Vec<Rc<str>>,
+    // Different for every table, so symbols given by others can be told apart
+    table: usize,
+}
+
+static TABLES: AtomicUsize = AtomicUsize::new(0);
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            names: Vec::new(),
+            table: TABLES.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn table(&self) -> usize {
+        self.table
+    }
+
+    // Get the symbol for a name, giving it the next number if it hasn't been seen before
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&id) = self.ids.get(name) {
+            return Symbol {
+                id,
+                name: Rc::clone(&self.names[id]),
+                table: self.table,
+            };
+        }
+
+        let id = self.names.len();
+        let name: Rc<str> = Rc::from(name);
+
+        self.names.push(Rc::clone(&name));
+        self.ids.insert(Rc::clone(&name), id);
+
+        Symbol {
+            id,
+            name,
+            table: self.table,
+        }
+    }
+
+    // Find the symbol for a name without adding it
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.ids.get(name).map(|&id| Symbol {
+            id,
+            name: Rc::clone(&self.names[id]),
+            table: self.table,
+        })
+    }
+
+    // The symbol a number was given to
+    pub fn symbol(&self, id: usize) -> Option<Symbol> {
+        self.names.get(id).map(|name| Symbol {
+            id,
+            name: Rc::clone(name),
+            table: self.table,
+        })
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Symbol numbers are already unique, so hashing them only needs to spread them out
+#[derive(Default)]
+pub struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 << 8 | *b as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        }
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.0 = (n as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+// A map from symbol numbers, used for variables, or anything else keyed by a unique number
+pub type SymbolMap<V> = HashMap<usize, V, BuildHasherDefault<IdHasher>>;
@@ -0,0 +1,126 @@
+// Calling lisp and reading its variables from rust
+
+use lithia::{
+    lisp::{Lisp, LispBuilder},
+    object::Object,
+    rc::Rc,
+};
+
+fn new_lisp() -> Lisp {
+    LispBuilder::new().add_default_envs().unwrap().build()
+}
+
+#[test]
+fn variables() {
+    let mut lisp = new_lisp();
+
+    lisp.define("width", 3.0).unwrap();
+    lisp.eval("(set area (* width width))").unwrap();
+
+    assert_eq!(lisp.get::<f32>("area").unwrap(), 9.0);
+    assert!(lisp.get::<String>("area").is_err());
+    assert!(lisp.get::<f32>("never-seen").is_err());
+    assert!(!lisp.contains("never-seen"));
+    assert!(lisp.define("width", 4.0).is_err());
+
+    lisp.set("width", "wide").unwrap();
+    assert_eq!(lisp.get::<String>("width").unwrap(), "wide");
+
+    assert!(lisp.remove("area").is_some());
+    assert!(!lisp.contains("area"));
+    assert!(lisp.remove("area").is_none());
+}
+
+#[test]
+fn calls() {
+    let mut lisp = new_lisp();
+
+    lisp.eval("(defunc mean (xs) (/ (apply + xs) (length xs)))")
+        .unwrap();
+
+    assert_eq!(
+        lisp.call::<f32>("mean", (vec![1.0, 2.0, 6.0],)).unwrap(),
+        3.0
+    );
+    assert!(lisp.call::<String>("mean", (vec![1.0],)).is_err());
+    assert!(lisp.call::<f32>("never-seen", ()).is_err());
+
+    let join = lisp.eval("string-append").unwrap();
+    let joined: String = lisp.call_value(&join, ("a", "b")).unwrap();
+
+    assert_eq!(joined, "ab");
+}
+
+// Symbols read without the interpreter's table are found by name, not by their number in the
+// table they were read with
+#[test]
+#[allow(deprecated)]
+fn symbols_read_elsewhere() {
+    for bytecode in [false, true] {
+        let mut lisp = new_lisp();
+        lisp.set_bytecode(bytecode);
+
+        lisp.define("width", 3.0).unwrap();
+
+        let code = "(defunc area (height) (* width height)) (def depth 2) (area width)";
+        let objects = Object::eval(code)
+            .unwrap()
+            .into_iter()
+            .map(Rc::new)
+            .collect();
+
+        assert_eq!(lisp.eval_objects(objects).unwrap().to_string(), "9");
+        assert_eq!(lisp.get::<f32>("depth").unwrap(), 2.0);
+
+        let objects = Object::eval("width")
+            .unwrap()
+            .into_iter()
+            .map(Rc::new)
+            .collect();
+        assert_eq!(lisp.eval_objects(objects).unwrap().to_string(), "3");
+    }
+}
+
+#[test]
+fn collecting_garbage() {
+    let mut lisp = new_lisp();
+
+    lisp.eval("(def x (list 1 2)) (set-cdr! (cdr x) x) (set x ())")
+        .unwrap();
+
+    // Another interpreter sharing the heap could be using it from another thread
+    let other = lisp.clone();
+    #[cfg(feature = "sync")]
+    assert_eq!(lisp.collect_garbage(), None);
+    drop(other);
+
+    assert_eq!(lisp.collect_garbage(), Some(2));
+    assert_eq!(lisp.heap_stats().candidates, 0);
+}
+
+// Interpreters sharing globals wait for each other rather than panicking
+#[cfg(feature = "sync")]
+#[test]
+fn threads() {
+    let mut lisp = new_lisp();
+
+    lisp.eval("(def v (vector))").unwrap();
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let mut lisp = lisp.clone();
+
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    lisp.eval("(vector-push! v (vector-length v))").unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    assert_eq!(lisp.eval("(vector-length v)").unwrap().to_string(), "400");
+}
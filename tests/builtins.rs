@@ -0,0 +1,331 @@
+// The builtins added by the default environments, checked by what they return when written out
+
+use lithia::lisp::{Lisp, LispBuilder};
+
+fn new_lisp(bytecode: bool) -> Lisp {
+    LispBuilder::new()
+        .add_default_envs()
+        .unwrap()
+        .use_bytecode(bytecode)
+        .build()
+}
+
+// Evaluate code, giving the last object written as code
+fn eval(lisp: &mut Lisp, code: &str) -> String {
+    match lisp.eval(code) {
+        Ok(o) => o.to_string(),
+        Err(e) => panic!("{} gave an error: {}", code, e),
+    }
+}
+
+// Check each piece of code evaluates to what it is paired with, both walking the objects and
+// running them as bytecode
+fn assert_evals(cases: &[(&str, &str)]) {
+    for bytecode in [false, true] {
+        let mut lisp = new_lisp(bytecode);
+
+        for (code, expected) in cases {
+            assert_eq!(
+                eval(&mut lisp, code),
+                *expected,
+                "evaluating {} with bytecode {}",
+                code,
+                bytecode
+            );
+        }
+    }
+}
+
+fn assert_errors(cases: &[&str]) {
+    for bytecode in [false, true] {
+        let mut lisp = new_lisp(bytecode);
+
+        for code in cases {
+            assert!(lisp.eval(code).is_err(), "{} should give an error", code);
+        }
+    }
+}
+
+#[test]
+fn strings() {
+    assert_evals(&[
+        (r#"(string-length "hello")"#, "5"),
+        (r#"(string-append "ab" "cd" "")"#, r#""abcd""#),
+        (r#"(substring "hello" 1 3)"#, r#""el""#),
+        (r#"(substring "hello" 2)"#, r#""llo""#),
+        (r#"(string-split "a,b,c" ",")"#, r#"("a" "b" "c")"#),
+        (r#"(string-split " a  b ")"#, r#"("a" "b")"#),
+        (r#"(string-join '("a" "b") "-")"#, r#""a-b""#),
+        (r#"(string-index "hello" \l)"#, "2"),
+        (r#"(string-contains? "hello" "ell")"#, "t"),
+        (r#"(string-upcase "abc")"#, r#""ABC""#),
+        (r#"(string-downcase "ABC")"#, r#""abc""#),
+        (r#"(string-trim "  x  ")"#, r#""x""#),
+        (
+            r#"(list->string (reverse (string->list "abc")))"#,
+            r#""cba""#,
+        ),
+        (r#"(string=? "a" "a")"#, "t"),
+        (r#"(string<? "a" "b")"#, "t"),
+        (r#"(number->string 255 16)"#, r#""ff""#),
+        (r#"(string->number "ff" 16)"#, "255"),
+        (r#"(string->number "x")"#, "()"),
+        (
+            r#"(format "~a has ~d items~~" "list" 3)"#,
+            r#""list has 3 items~""#,
+        ),
+        (r#"(format "~s" "q")"#, r#""\"q\"""#),
+    ]);
+
+    assert_errors(&[
+        r#"(substring "abc" 2 1)"#,
+        r#"(substring "abc" 0 9)"#,
+        r#"(number->string 1 10 1e10)"#,
+    ]);
+}
+
+#[test]
+fn regex() {
+    assert_evals(&[
+        (r#"(regex-match? "^a+$" "aaa")"#, "t"),
+        (r#"(regex-match? "^a+$" "ab")"#, "()"),
+        (
+            r#"(regex-find "(\w+)@(\w+)" "mail bob@example")"#,
+            r#"("bob@example" "bob" "example")"#,
+        ),
+        (r#"(regex-find "(a)|(b)" "b")"#, r#"("b" () "b")"#),
+        (r#"(regex-find-all "\d" "a1b2")"#, r#"(("1") ("2"))"#),
+        (
+            r#"(regex-replace "(\w+)" "hi yo" "<$1>")"#,
+            r#""<hi> <yo>""#,
+        ),
+        (r#"(regex-split "," "a,b")"#, r#"("a" "b")"#),
+        (r#"(equal? (regex "a+") (regex "a+"))"#, "t"),
+        (r#"(regex-match? (regex "b") "abc")"#, "t"),
+        (r#"(type-of (regex "x"))"#, "regex"),
+        ("(def patterns (make-hash-table))", "()"),
+        (r#"(hash-set! patterns (regex "a+") 1)"#, "()"),
+        (r#"(hash-set! patterns (regex "b+") 2)"#, "()"),
+        (r#"(hash-ref patterns (regex "b+"))"#, "2"),
+    ]);
+
+    assert_errors(&[r#"(regex "(")"#, r#"(regex-match? "[" "a")"#]);
+}
+
+#[test]
+fn characters() {
+    assert_evals(&[
+        (r"(char->integer \A)", "65"),
+        (r"(integer->char 97)", r"\a"),
+        (r"\space", r"\space"),
+        (r"\u{41}", r"\A"),
+        (r"(char-alphabetic? \a)", "t"),
+        (r"(char-numeric? \a)", "()"),
+        (r"(char-whitespace? \tab)", "t"),
+        (r"(char-upcase \a)", r"\A"),
+        (r"(char-downcase \A)", r"\a"),
+    ]);
+
+    assert_errors(&["(integer->char 4294967808)", "(integer->char 55296)"]);
+}
+
+#[test]
+fn lists() {
+    assert_evals(&[
+        ("(list 1 2 3)", "(1 2 3)"),
+        ("(length '(a b c))", "3"),
+        ("(append '(1) '(2 3) '(4))", "(1 2 3 4)"),
+        ("(append '(1) 2)", "(1 . 2)"),
+        ("(reverse '(1 2 3))", "(3 2 1)"),
+        ("(nth 1 '(a b c))", "b"),
+        ("(nth 5 '(a b c))", "()"),
+        ("(nthcdr 2 '(a b c))", "(c)"),
+        ("(last '(a b c))", "c"),
+        ("(member 2 '(1 2 3))", "(2 3)"),
+        ("(assoc 'b '((a . 1) (b . 2)))", "(b . 2)"),
+        ("(list-copy '(1 2))", "(1 2)"),
+        ("(range 0 10 3)", "(0 3 6 9)"),
+        ("(iota 3 1)", "(1 2 3)"),
+        ("(null? ())", "t"),
+        ("(pair? '(1))", "t"),
+        ("(list? '(1 . 2))", "()"),
+    ]);
+
+    assert_errors(&[
+        "(length '(1 . 2))",
+        "(iota -1)",
+        "(iota 1e30)",
+        "(range 0 1e30)",
+        "(range 0 1 0)",
+    ]);
+}
+
+#[test]
+fn mutable_pairs() {
+    assert_evals(&[
+        ("(def a (list 1 2))", "()"),
+        ("(set-car! a 0)", "()"),
+        ("a", "(0 2)"),
+        ("(set-cdr! (cdr a) a)", "()"),
+        ("a", "#0=(0 2 . #0#)"),
+        ("(equal? a '#0=(0 2 0 2 . #0#))", "t"),
+        ("(def v (vector 1))", "()"),
+        ("(vector-push! v v)", "()"),
+        ("v", "#0=#(1 #0#)"),
+        ("(equal? v v)", "t"),
+        ("(def c (list 1 2 3))", "()"),
+        ("(set-cdr! (cdr (cdr c)) c)", "()"),
+        ("(member 3 c)", "#0=(3 1 2 . #0#)"),
+    ]);
+
+    assert_errors(&["(def c (list 1)) (set-cdr! c c) (member 9 c)"]);
+}
+
+#[test]
+fn higher_order() {
+    assert_evals(&[
+        ("(apply + 1 2 '(3 4))", "10"),
+        ("(funcall * 2 3)", "6"),
+        ("(map + '(1 2 3) '(10 20))", "(11 22)"),
+        ("(filter even? '(1 2 3 4))", "(2 4)"),
+        ("(reduce + '(1 2 3))", "6"),
+        ("(reduce + '() 5)", "5"),
+        ("(fold-left - 0 '(1 2))", "-3"),
+        ("(fold-right - 0 '(1 2))", "-1"),
+        ("(any odd? '(2 3))", "t"),
+        ("(every odd? '(1 2))", "()"),
+        ("(find odd? '(2 3 5))", "3"),
+        (r#"(sort '("b" "c" "a") string<?)"#, r#"("a" "b" "c")"#),
+        ("(sort '(3 1 2) (func (a b) (< a b)))", "(1 2 3)"),
+    ]);
+}
+
+#[test]
+fn comparisons() {
+    assert_evals(&[
+        ("(< 1 2 3)", "t"),
+        ("(< 1 3 2)", "()"),
+        ("(>= 3 3 1)", "t"),
+        ("(min 3 1 2)", "1"),
+        ("(max 3 1 2)", "3"),
+        ("(abs -2)", "2"),
+        ("(zero? 0)", "t"),
+        ("(negative? -1)", "t"),
+        ("(odd? 3)", "t"),
+        ("(eq? '(1) '(1))", "()"),
+        ("(eqv? 1 1)", "t"),
+        ("(equal? '(1 #(2)) '(1 #(2)))", "t"),
+        ("(equal? car car)", "t"),
+    ]);
+}
+
+#[test]
+fn types() {
+    assert_evals(&[
+        (r#"(type-of "hello")"#, "pair"),
+        ("(type-of 1)", "number"),
+        ("(type-of :a)", "keyword"),
+        ("(type-of #(1))", "vector"),
+        ("(type-of #hash())", "hash-table"),
+        ("(type-of car)", "builtin"),
+        ("(type-of (func (x) x))", "function"),
+        ("(number? 1)", "t"),
+        (r#"(string? "")"#, "t"),
+        ("(procedure? car)", "t"),
+        ("(keyword? :a)", "t"),
+        ("(keyword->symbol :name)", "name"),
+        ("(symbol->keyword 'name)", ":name"),
+        (":a", ":a"),
+        ("((func (a &key b c) (list a b c)) 1 :c 3)", "(1 () 3)"),
+    ]);
+
+    assert_errors(&["((func (&key b) b) :c 1)"]);
+}
+
+#[test]
+fn vectors() {
+    assert_evals(&[
+        ("(vector 1 2)", "#(1 2)"),
+        ("(make-vector 3 0)", "#(0 0 0)"),
+        ("(vector-ref #(a b c) 1)", "b"),
+        ("(def v (vector 1 2))", "()"),
+        ("(vector-set! v 0 9)", "()"),
+        ("v", "#(9 2)"),
+        ("(vector-length v)", "2"),
+        ("(vector->list v)", "(9 2)"),
+        ("(list->vector '(1))", "#(1)"),
+        ("(vector-map (func (x) (* x 2)) #(1 2))", "#(2 4)"),
+    ]);
+
+    assert_errors(&[
+        "(vector-ref #(1) 1)",
+        "(make-vector -1)",
+        "(make-vector 1e30)",
+    ]);
+}
+
+#[test]
+fn hash_tables() {
+    assert_evals(&[
+        ("(def h (make-hash-table '((a . 1))))", "()"),
+        ("(hash-ref h 'a)", "1"),
+        ("(hash-ref h 'b 0)", "0"),
+        ("(hash-set! h '(1 2) 'list)", "()"),
+        ("(hash-ref h (list 1 2))", "list"),
+        ("(hash-count h)", "2"),
+        ("(hash-remove! h 'a)", "1"),
+        ("(hash-keys h)", "((1 2))"),
+        ("(hash-values h)", "(list)"),
+        ("(hash->list h)", "(((1 2) . list))"),
+        ("(hash-set! h h 1)", "()"),
+        ("(hash-ref h h)", "1"),
+    ]);
+
+    assert_errors(&[
+        "(hash-set! (make-hash-table) (/ 0 0) 1)",
+        "(def l (list 1)) (set-cdr! l l) (hash-set! (make-hash-table) l 1)",
+    ]);
+}
+
+#[test]
+fn functions() {
+    assert_evals(&[
+        ("(defunc add3 (a b c) (+ a b c))", "()"),
+        ("(add3 1 2 3)", "6"),
+        ("(defunc bump (n) (set n (+ n 1)) n)", "()"),
+        ("(bump 1)", "2"),
+        (
+            "(defunc count (n) (set i 0) (while (< i n) (set i (+ i 1))) i)",
+            "()",
+        ),
+        ("(count 5)", "5"),
+        ("(defunc wrap (x) (list x))", "()"),
+        ("(wrap (+ 1 2))", "(3)"),
+        // Builtins which evaluate their arguments may be replaced by ones which don't
+        ("(set list quote)", "()"),
+        ("(wrap 1)", "x"),
+    ]);
+}
+
+// Variables made inside if and while go when they do, even when they stop with an error
+#[test]
+fn errors_end_scopes() {
+    for bytecode in [false, true] {
+        let mut lisp = new_lisp(bytecode);
+
+        assert!(lisp.eval("(if (set a 1) 1 (car 1))").is_err());
+        assert!(lisp.eval("(while t (set b 1) (car 1))").is_err());
+        assert!(lisp
+            .eval("(defunc f () (if (set c 1) 1 (car 1))) (f)")
+            .is_err());
+
+        for name in ["a", "b", "c"] {
+            assert!(
+                !lisp.contains(name),
+                "{} kept with bytecode {}",
+                name,
+                bytecode
+            );
+        }
+    }
+}
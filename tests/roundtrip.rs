@@ -0,0 +1,205 @@
+// Objects written out as code should read back in as equal objects, including those which
+// contain themselves and are written with #n= labels
+
+use lithia::{
+    lisp::{Lisp, LispBuilder},
+    object::{HashTable, Object},
+    rc::{Rc, RefCell},
+};
+use proptest::prelude::*;
+
+// An object to build, kept apart from the interpreter so proptest can shrink it
+#[derive(Debug, Clone)]
+enum Tree {
+    Nil,
+    Number(f32),
+    Character(char),
+    String(String),
+    Symbol(String),
+    Keyword(String),
+    Quoted(Box<Tree>),
+    List(Vec<Tree>, Option<Box<Tree>>),
+    Vector(Vec<Tree>),
+    HashTable(Vec<(Tree, Tree)>),
+}
+
+// Names which would be read as something other than a symbol
+fn is_symbol(name: &str) -> bool {
+    name.parse::<f32>().is_err()
+}
+
+fn tree() -> impl Strategy<Value = Tree> {
+    let leaf = prop_oneof![
+        Just(Tree::Nil),
+        (-1e6f32..1e6).prop_map(Tree::Number),
+        any::<char>().prop_map(Tree::Character),
+        any::<String>().prop_map(Tree::String),
+        "[a-z][a-z0-9?!*<>=-]{0,8}"
+            .prop_filter("must be a symbol", |s| is_symbol(s))
+            .prop_map(Tree::Symbol),
+        "[a-z][a-z0-9-]{0,8}".prop_map(Tree::Keyword),
+    ];
+
+    leaf.prop_recursive(4, 32, 6, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|t| Tree::Quoted(Box::new(t))),
+            (
+                prop::collection::vec(inner.clone(), 1..6),
+                prop::option::of(inner.clone().prop_filter("must not be a list", |t| {
+                    !matches!(t, Tree::Nil | Tree::List(..) | Tree::String(_))
+                })),
+            )
+                .prop_map(|(items, tail)| Tree::List(items, tail.map(Box::new))),
+            prop::collection::vec(inner.clone(), 0..6).prop_map(Tree::Vector),
+            prop::collection::vec((inner.clone(), inner), 0..6).prop_map(Tree::HashTable),
+        ]
+    })
+}
+
+fn build(lisp: &Lisp, tree: &Tree) -> Rc<Object> {
+    Rc::new(match tree {
+        Tree::Nil => Object::Nil,
+        Tree::Number(n) => Object::Number(*n),
+        Tree::Character(c) => Object::Character(*c),
+        Tree::String(s) => Object::string_to_lisp_string(s),
+        Tree::Symbol(s) => Object::Symbol(lisp.intern(s)),
+        Tree::Keyword(s) => Object::Keyword(s.clone()),
+        Tree::Quoted(t) => Object::Quoted(build(lisp, t)),
+        Tree::List(items, tail) => {
+            let mut list = match tail {
+                Some(t) => build(lisp, t),
+                None => Rc::new(Object::Nil),
+            };
+
+            for t in items.iter().rev() {
+                list = Rc::new(Object::pair(build(lisp, t), list));
+            }
+
+            return list;
+        }
+        Tree::Vector(items) => {
+            Object::Vector(RefCell::new(items.iter().map(|t| build(lisp, t)).collect()))
+        }
+        Tree::HashTable(pairs) => {
+            let mut table = HashTable::new();
+
+            for (k, v) in pairs {
+                let key = table.key(build(lisp, k)).unwrap();
+                table.insert(key, build(lisp, v));
+            }
+
+            Object::HashTable(RefCell::new(table))
+        }
+    })
+}
+
+fn new_lisp() -> Lisp {
+    LispBuilder::new().add_default_envs().unwrap().build()
+}
+
+// Write an object and check it reads back as the same. It may not be written the same way again,
+// as tables keep their keys in no particular order
+fn assert_roundtrip(lisp: &Lisp, object: &Rc<Object>) -> Result<(), TestCaseError> {
+    let written = object.to_string();
+    let read = lisp
+        .read(&written)
+        .map_err(|e| TestCaseError::fail(format!("{} didn't read back: {}", written, e)))?;
+
+    prop_assert_eq!(read.len(), 1, "{} read back as {:?}", written, read);
+    prop_assert!(read[0] == *object, "{} read back as {}", written, read[0]);
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn objects_read_back(tree in tree()) {
+        let lisp = new_lisp();
+
+        assert_roundtrip(&lisp, &build(&lisp, &tree))?;
+    }
+
+    // A list whose end goes back to its start
+    #[test]
+    fn circular_lists_read_back(items in prop::collection::vec(tree(), 1..6)) {
+        let lisp = new_lisp();
+        let list = build(&lisp, &Tree::List(items, None));
+
+        let mut last = Rc::clone(&list);
+        while let Object::Pair(_, b) = &*Rc::clone(&last) {
+            if !matches!(*b.get(), Object::Pair(..)) {
+                b.set(Rc::clone(&list));
+                break;
+            }
+
+            last = b.get();
+        }
+
+        assert_roundtrip(&lisp, &list)?;
+    }
+
+    // Vectors and tables which are one of their own items
+    #[test]
+    fn containers_holding_themselves_read_back(
+        items in prop::collection::vec(tree(), 0..6),
+        key in tree(),
+    ) {
+        let lisp = new_lisp();
+
+        let vector = build(&lisp, &Tree::Vector(items.clone()));
+        if let Object::Vector(v) = &*vector {
+            v.borrow_mut().push(Rc::clone(&vector));
+        }
+
+        assert_roundtrip(&lisp, &vector)?;
+
+        let table = build(&lisp, &Tree::HashTable(Vec::new()));
+        if let Object::HashTable(t) = &*table {
+            let key = t.borrow().key(build(&lisp, &key)).unwrap();
+            t.borrow_mut().insert(key, Rc::new(Object::pair(Rc::clone(&vector), Rc::clone(&table))));
+        }
+
+        assert_roundtrip(&lisp, &table)?;
+    }
+}
+
+#[test]
+fn labels_are_shared() {
+    let mut lisp = new_lisp();
+
+    let list = lisp.eval("'#0=(a b . #0#)").unwrap();
+    assert_eq!(list.to_string(), "#0=(a b . #0#)");
+
+    lisp.eval("(def l '#0=(1 #0#))").unwrap();
+    assert_eq!(lisp.eval("(eq? l (car (cdr l)))").unwrap().to_string(), "t");
+    assert_eq!(lisp.eval("'(#1=(x) #1#)").unwrap().to_string(), "((x) (x))");
+}
+
+// Strings are only lists of characters, so these are written differently to how they were made but
+// still read back as the same
+#[test]
+fn strings_are_lists_of_characters() {
+    let mut lisp = new_lisp();
+
+    for (code, written) in [
+        (r#""""#, "()"),
+        (r"(list \a \b)", r#""ab""#),
+        (r"(cons \a ())", r#""a""#),
+        (r"(list \a 1)", r"(\a 1)"),
+    ] {
+        let object = lisp.eval(code).unwrap();
+        assert_eq!(object.to_string(), written, "writing {}", code);
+
+        let read = lisp.read(written).unwrap();
+        assert!(read[0] == object, "{} read back as {}", written, read[0]);
+    }
+}
+
+#[test]
+fn bad_labels_are_errors() {
+    let lisp = new_lisp();
+
+    assert!(lisp.read("#0#").is_err());
+    assert!(lisp.read("#0=#0#").is_err());
+    assert!(lisp.read("(#0=a #1#)").is_err());
+}